@@ -0,0 +1,95 @@
+//! Compares firing large volleys with and without the projectile pool.
+//!
+//! Run with `cargo bench --bench projectile_pool`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use infinite_shooter_game::gun::{Bullet, BulletVelocity};
+use infinite_shooter_game::projectile::{release_projectile, ProjectileModifiers, ProjectilePool};
+
+const PROJECTILES_PER_SHOT: usize = 100;
+const SHOTS: usize = 2000;
+
+fn projectile_bundle(i: usize) -> impl Bundle {
+    (
+        SpriteBundle {
+            transform: Transform::from_xyz(i as f32, 0.0, 1.0),
+            ..default()
+        },
+        TextureAtlas::default(),
+        Bullet { damage: 10.0 },
+        BulletVelocity(Vec3::X * 1000.0),
+    )
+}
+
+/// Fires `SHOTS` volleys the way the gun did before the pool: a fresh
+/// entity per projectile, despawned when it is done.
+fn spawn_and_despawn() -> Duration {
+    let mut world = World::new();
+    let modifiers = ProjectileModifiers::default();
+    let mut volley = Vec::with_capacity(PROJECTILES_PER_SHOT);
+
+    let start = Instant::now();
+    for _ in 0..SHOTS {
+        let mut commands = world.commands();
+        for i in 0..PROJECTILES_PER_SHOT {
+            let mut projectile = commands.spawn(projectile_bundle(i));
+            modifiers.insert_into(&mut projectile);
+            volley.push(projectile.id());
+        }
+        world.flush();
+
+        let mut commands = world.commands();
+        for projectile in volley.drain(..) {
+            commands.entity(black_box(projectile)).despawn();
+        }
+        world.flush();
+    }
+    start.elapsed()
+}
+
+/// Fires `SHOTS` volleys through a pool of `pool_size`, releasing each one
+/// before the next.
+fn fire_volleys(pool_size: usize) -> Duration {
+    let mut world = World::new();
+    world.insert_resource(ProjectilePool::new(pool_size));
+    let modifiers = ProjectileModifiers::default();
+    let mut volley = Vec::with_capacity(PROJECTILES_PER_SHOT);
+
+    let start = Instant::now();
+    for _ in 0..SHOTS {
+        world.resource_scope(|world, mut pool: Mut<ProjectilePool>| {
+            let mut commands = world.commands();
+            for i in 0..PROJECTILES_PER_SHOT {
+                let mut projectile = pool.spawn(&mut commands, projectile_bundle(i));
+                modifiers.insert_into(&mut projectile);
+                volley.push(projectile.id());
+            }
+        });
+        world.flush();
+
+        let mut commands = world.commands();
+        for projectile in volley.drain(..) {
+            release_projectile(&mut commands, black_box(projectile));
+        }
+        world.flush();
+    }
+    start.elapsed()
+}
+
+fn main() {
+    // Warm up allocations and archetypes before timing.
+    spawn_and_despawn();
+    fire_volleys(PROJECTILES_PER_SHOT);
+
+    let unpooled = spawn_and_despawn();
+    let exhausted = fire_volleys(0);
+    let pooled = fire_volleys(PROJECTILES_PER_SHOT);
+    let per_shot = |total: Duration| total / SHOTS as u32;
+    println!("{SHOTS} shots of {PROJECTILES_PER_SHOT} projectiles");
+    println!("spawn/despawn: {:?} per shot", per_shot(unpooled));
+    println!("empty pool:    {:?} per shot", per_shot(exhausted));
+    println!("pooled:        {:?} per shot", per_shot(pooled));
+}
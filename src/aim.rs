@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::collision::EnemyKdTree;
+use crate::enemy::Enemy;
+use crate::game_config::GameConfig;
+use crate::input::{PlayerInput, PlayerInputSet};
+use crate::player::Player;
+use crate::state::GameState;
+use crate::*;
+
+pub struct AimPlugin;
+
+/// Point the gun aims at this frame: the cursor or right stick direction, or
+/// an enemy picked by the configured `AimMode`.
+#[derive(Resource, Default)]
+pub struct AimPosition(pub Option<Vec2>);
+
+/// Systems that fill `AimPosition`; they run after `PlayerInputSet`.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AimSet;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AimMode {
+    /// Aim exactly at the cursor or along the right stick.
+    #[default]
+    Manual,
+    /// Nudge the aim direction toward the closest enemy in a cone around it.
+    Assist,
+    /// Ignore the cursor and right stick while an enemy is in range.
+    Auto(AutoAimTarget),
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoAimTarget {
+    Nearest,
+    LowestHealth,
+    /// Most health per unit of distance to the player, so strong enemies
+    /// closing in come first.
+    HighestThreat,
+}
+
+impl Plugin for AimPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AimPosition>()
+            .configure_sets(Update, AimSet.after(PlayerInputSet))
+            .add_systems(
+                Update,
+                update_aim_position
+                    .in_set(AimSet)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn update_aim_position(
+    config: Res<GameConfig>,
+    input: Res<PlayerInput>,
+    cursor_pos: Res<CursorPosition>,
+    tree: Res<EnemyKdTree>,
+    player_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<(&Transform, &Enemy)>,
+    mut aim_pos: ResMut<AimPosition>,
+) {
+    aim_pos.0 = cursor_pos.0;
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    let player_pos = player_transform.translation.truncate();
+    if let Some(dir) = input.aim {
+        aim_pos.0 = Some(player_pos + dir * GAMEPAD_AIM_DISTANCE);
+    }
+    let aim = &config.aim;
+    if aim.mode == AimMode::Manual {
+        return;
+    }
+    let enemies = tree
+        .within_radius(player_pos, aim.range)
+        .filter_map(|entity| enemy_query.get(entity).ok())
+        .map(|(transform, enemy)| (transform.translation.truncate(), enemy.health))
+        .filter(|(pos, _)| pos.distance(player_pos) <= aim.range);
+
+    match aim.mode {
+        AimMode::Manual => {}
+        AimMode::Assist => {
+            let Some(manual_aim) = aim_pos.0 else {
+                return;
+            };
+            let aim_dir = manual_aim - player_pos;
+            let half_cone = aim.assist_cone_degrees.to_radians() / 2.0;
+            let target = enemies
+                .map(|(pos, _)| (pos, aim_dir.angle_between(pos - player_pos).abs()))
+                .filter(|(_, angle)| *angle <= half_cone)
+                .min_by(|(_, a), (_, b)| a.total_cmp(b));
+            if let Some((target, _)) = target {
+                let dir = assist_direction(aim_dir, target - player_pos, aim.assist_strength);
+                aim_pos.0 = Some(player_pos + dir);
+            }
+        }
+        AimMode::Auto(target) => {
+            let score = |(pos, health): &(Vec2, f32)| {
+                let dist = pos.distance(player_pos).max(1.0);
+                match target {
+                    AutoAimTarget::Nearest => -dist,
+                    AutoAimTarget::LowestHealth => -health,
+                    AutoAimTarget::HighestThreat => health / dist,
+                }
+            };
+            if let Some((pos, _)) = enemies.max_by(|a, b| score(a).total_cmp(&score(b))) {
+                aim_pos.0 = Some(pos);
+            }
+        }
+    }
+}
+
+/// Rotates `aim` toward `target` by `strength` (0 to 1) of the angle between
+/// them, keeping the length of `aim`.
+pub fn assist_direction(aim: Vec2, target: Vec2, strength: f32) -> Vec2 {
+    let angle = aim.angle_between(target);
+    if !angle.is_finite() {
+        return aim;
+    }
+    Vec2::from_angle(angle * strength).rotate(aim)
+}
@@ -1,11 +1,11 @@
 use bevy::prelude::*;
 
 use crate::{
+    aim::{AimPosition, AimSet},
     enemy::{Enemy, EnemyType},
     gun::Gun,
     player::{Player, PlayerState},
     state::GameState,
-    CursorPosition,
 };
 
 pub struct AnimationPlugin;
@@ -21,8 +21,7 @@ impl Plugin for AnimationPlugin {
                 animation_timer_tick,
                 animate_player,
                 animate_enemy,
-                flip_gun_sprite_y,
-                flip_player_sprite_x,
+                (flip_gun_sprite_y, flip_player_sprite_x).after(AimSet),
                 flip_enemy_sprite_x,
             )
                 .run_if(in_state(GameState::InGame)),
@@ -71,7 +70,7 @@ fn animate_enemy(
 }
 
 fn flip_player_sprite_x(
-    cursor_position: Res<CursorPosition>,
+    aim_position: Res<AimPosition>,
     mut player_query: Query<(&mut Sprite, &Transform), With<Player>>,
 ) {
     if player_query.is_empty() {
@@ -79,12 +78,8 @@ fn flip_player_sprite_x(
     }
 
     let (mut sprite, transform) = player_query.single_mut();
-    if let Some(cursor_position) = cursor_position.0 {
-        if cursor_position.x > transform.translation.x {
-            sprite.flip_x = false;
-        } else {
-            sprite.flip_x = true;
-        }
+    if let Some(aim_position) = aim_position.0 {
+        sprite.flip_x = aim_position.x <= transform.translation.x;
     }
 }
 
@@ -98,16 +93,12 @@ fn flip_enemy_sprite_x(
 
     let player_pos = player_query.single().translation;
     for (mut sprite, transform) in enemy_query.iter_mut() {
-        if transform.translation.x < player_pos.x {
-            sprite.flip_x = false;
-        } else {
-            sprite.flip_x = true;
-        }
+        sprite.flip_x = transform.translation.x >= player_pos.x;
     }
 }
 
 fn flip_gun_sprite_y(
-    cursor_position: Res<CursorPosition>,
+    aim_position: Res<AimPosition>,
     mut gun_query: Query<(&mut Sprite, &Transform), With<Gun>>,
 ) {
     if gun_query.is_empty() {
@@ -115,11 +106,7 @@ fn flip_gun_sprite_y(
     }
 
     let (mut sprite, transform) = gun_query.single_mut();
-    if let Some(cursor_position) = cursor_position.0 {
-        if cursor_position.x > transform.translation.x {
-            sprite.flip_y = false;
-        } else {
-            sprite.flip_y = true;
-        }
+    if let Some(aim_position) = aim_position.0 {
+        sprite.flip_y = aim_position.x <= transform.translation.x;
     }
 }
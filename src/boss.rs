@@ -0,0 +1,364 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use crate::animation::AnimationTimer;
+use crate::boss_def::{BossAttack, BossDef, BossDefs, BossMovement, BossPhase, BossTrigger};
+use crate::collision::collider_radius;
+use crate::enemy::{Enemy, EnemyDeathSet};
+use crate::game_config::GameConfig;
+use crate::pickup::drop_rewards;
+use crate::player::{Player, PlayerEnemyCollisionEvent};
+use crate::ranged::{spawn_enemy_projectile, EnemyProjectile};
+use crate::rng::{GameRng, RngStream};
+use crate::state::GameState;
+use crate::stats::RunStats;
+use crate::world::GameEntity;
+use crate::*;
+
+pub struct BossPlugin;
+
+/// A boss, on top of `Enemy` and the `EnemyType` it borrows the sprite of.
+/// The regular enemy systems leave it alone: it moves, attacks, touches the
+/// player and dies here.
+#[derive(Component)]
+pub struct Boss {
+    /// Index into `BossDefs`.
+    pub def: usize,
+    pub max_health: f32,
+    /// Index into the def's phases. Only ever goes up.
+    pub phase: usize,
+    attack_cooldown: Timer,
+    telegraph: Option<Timer>,
+    contact_cooldown: Timer,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct BossKilled {
+    pub def: usize,
+    pub position: Vec2,
+}
+
+/// Bosses already met this run.
+#[derive(Resource, Default)]
+struct BossSchedule {
+    spawned: HashSet<usize>,
+}
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BossSchedule>()
+            .add_event::<BossKilled>()
+            .add_systems(OnEnter(GameState::GameInit), reset_boss_schedule)
+            .add_systems(
+                Update,
+                (
+                    spawn_bosses,
+                    (update_boss_phases, move_bosses, update_boss_attacks).chain(),
+                    handle_boss_contact,
+                    despawn_dead_bosses.in_set(EnemyDeathSet),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn reset_boss_schedule(mut schedule: ResMut<BossSchedule>) {
+    schedule.spawned.clear();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_bosses(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    boss_defs: Res<BossDefs>,
+    stats: Res<RunStats>,
+    mut rng: ResMut<GameRng>,
+    mut schedule: ResMut<BossSchedule>,
+    handle: Res<GlobalTextureAtlas>,
+    player_query: Query<&Transform, With<Player>>,
+    boss_query: Query<(), With<Boss>>,
+) {
+    if player_query.is_empty() || !boss_query.is_empty() {
+        return;
+    }
+
+    let next = boss_defs.0.iter().enumerate().find(|(i, def)| {
+        !schedule.spawned.contains(i)
+            && match def.trigger {
+                BossTrigger::AfterSecs(secs) => stats.survival_time.as_secs_f32() >= secs,
+                BossTrigger::AfterKills(kills) => stats.total_kills() >= kills,
+            }
+    });
+    let Some((index, def)) = next else {
+        return;
+    };
+    schedule.spawned.insert(index);
+
+    let player_pos = player_query.single().translation.truncate();
+    let angle = rng.stream(RngStream::Enemies).gen_range(0.0..PI * 2.0);
+    let position = player_pos + Vec2::from_angle(angle) * BOSS_SPAWN_DISTANCE;
+    let max_health = def.health * config.enemy.health_scale;
+    commands.spawn((
+        SpriteBundle {
+            texture: handle.image.clone().unwrap(),
+            transform: Transform::from_translation(position.extend(1.0))
+                .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR * def.scale)),
+            ..default()
+        },
+        TextureAtlas {
+            layout: handle.layout.clone().unwrap(),
+            index: def.sprite.get_base_sprite_index(),
+        },
+        Enemy {
+            health: max_health,
+            last_hit_by: None,
+        },
+        def.sprite,
+        Boss::new(index, def, max_health),
+        AnimationTimer(Timer::from_seconds(0.08, TimerMode::Repeating)),
+        GameEntity,
+        RigidBody::Dynamic,
+        Collider::ball(def.collider_radius),
+        LockedAxes::ROTATION_LOCKED,
+        GravityScale(0.0),
+        ColliderMassProperties::Density(1.0),
+        AdditionalMassProperties::Mass(def.mass),
+    ));
+}
+
+fn update_boss_phases(
+    boss_defs: Res<BossDefs>,
+    mut boss_query: Query<(&Enemy, &mut Boss, &mut Sprite)>,
+) {
+    for (enemy, mut boss, mut sprite) in boss_query.iter_mut() {
+        let Some(def) = boss_defs.0.get(boss.def) else {
+            continue;
+        };
+        let health_fraction = enemy.health / boss.max_health;
+        let phase = def
+            .phases
+            .iter()
+            .rposition(|phase| phase.health_fraction >= health_fraction)
+            .unwrap_or(0);
+        if phase <= boss.phase {
+            continue;
+        }
+
+        boss.phase = phase;
+        boss.telegraph = None;
+        sprite.color = Color::WHITE;
+        if let Some(attack) = &def.phases[phase].attack {
+            boss.attack_cooldown = Timer::from_seconds(attack.interval_secs, TimerMode::Once);
+        }
+    }
+}
+
+fn move_bosses(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    boss_defs: Res<BossDefs>,
+    player_query: Query<&Transform, With<Player>>,
+    mut boss_query: Query<(&mut Transform, &Boss), Without<Player>>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let player_pos = player_query.single().translation.truncate();
+    for (mut transform, boss) in boss_query.iter_mut() {
+        let Some(phase) = boss.current_phase(&boss_defs) else {
+            continue;
+        };
+        if boss.telegraph.is_some() {
+            continue;
+        }
+
+        let offset = transform.translation.truncate() - player_pos;
+        let Some(away) = offset.try_normalize() else {
+            continue;
+        };
+        let dir = match phase.movement {
+            BossMovement::Chase => -away,
+            BossMovement::Orbit { distance } => {
+                let radial = if offset.length() > distance + RANGED_DISTANCE_TOLERANCE {
+                    -1.0
+                } else if offset.length() < distance - RANGED_DISTANCE_TOLERANCE {
+                    1.0
+                } else {
+                    0.0
+                };
+                (away * radial + away.perp()).normalize()
+            }
+        };
+        let speed = phase.speed * config.enemy.speed_scale;
+        transform.translation += (dir * speed * time.delta_seconds()).extend(0.0);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_boss_attacks(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    boss_defs: Res<BossDefs>,
+    handle: Res<GlobalTextureAtlas>,
+    player_query: Query<&Transform, With<Player>>,
+    mut boss_query: Query<(&Transform, &mut Boss, &mut Sprite), Without<Player>>,
+    projectile_query: Query<(), With<EnemyProjectile>>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let player_pos = player_query.single().translation.truncate();
+    let mut num_projectiles = projectile_query.iter().len();
+    for (transform, mut boss, mut sprite) in boss_query.iter_mut() {
+        let Some(attack) = boss
+            .current_phase(&boss_defs)
+            .and_then(|phase| phase.attack.as_ref())
+        else {
+            continue;
+        };
+
+        let Some(telegraph) = boss.telegraph.as_mut() else {
+            if boss.attack_cooldown.tick(time.delta()).finished() {
+                let (r, g, b) = TELEGRAPH_COLOR;
+                sprite.color = Color::srgb(r, g, b);
+                boss.telegraph = Some(Timer::from_seconds(attack.telegraph_secs, TimerMode::Once));
+            }
+            continue;
+        };
+        if !telegraph.tick(time.delta()).finished() {
+            continue;
+        }
+
+        sprite.color = Color::WHITE;
+        boss.telegraph = None;
+        boss.attack_cooldown = Timer::from_seconds(attack.interval_secs, TimerMode::Once);
+        let pos = transform.translation.truncate();
+        let Some(aim) = (player_pos - pos).try_normalize() else {
+            continue;
+        };
+        let room = config.enemy.max_projectiles.saturating_sub(num_projectiles);
+        for dir in volley_directions(attack, aim).take(room) {
+            num_projectiles += 1;
+            spawn_enemy_projectile(
+                &mut commands,
+                &handle,
+                pos,
+                dir * attack.projectile_speed,
+                attack.projectile_damage * config.enemy.damage_scale,
+                attack.projectile_lifetime_secs,
+            );
+        }
+    }
+}
+
+/// Touching a boss hurts once every `contact_interval_secs`, instead of
+/// continuously like regular enemies.
+fn handle_boss_contact(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    boss_defs: Res<BossDefs>,
+    player_query: Query<(&Transform, &Collider), With<Player>>,
+    mut boss_query: Query<(&Transform, &Collider, &mut Boss), Without<Player>>,
+    mut ew: EventWriter<PlayerEnemyCollisionEvent>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let (player_transform, player_collider) = player_query.single();
+    let player_pos = player_transform.translation.truncate();
+    let player_radius = collider_radius(player_collider, player_transform);
+    for (transform, collider, mut boss) in boss_query.iter_mut() {
+        boss.contact_cooldown.tick(time.delta());
+        let Some(def) = boss_defs.0.get(boss.def) else {
+            continue;
+        };
+        let reach = player_radius + collider_radius(collider, transform) + BOSS_CONTACT_MARGIN;
+        if !boss.contact_cooldown.finished()
+            || transform.translation.truncate().distance(player_pos) > reach
+        {
+            continue;
+        }
+
+        boss.contact_cooldown = Timer::from_seconds(def.contact_interval_secs, TimerMode::Once);
+        ew.send(PlayerEnemyCollisionEvent {
+            damage: def.contact_damage * config.enemy.damage_scale,
+        });
+    }
+}
+
+fn despawn_dead_bosses(
+    mut commands: Commands,
+    boss_defs: Res<BossDefs>,
+    handle: Res<GlobalTextureAtlas>,
+    boss_query: Query<(&Enemy, &Boss, &Transform, Entity)>,
+    mut ew: EventWriter<BossKilled>,
+) {
+    for (enemy, boss, transform, entity) in boss_query.iter() {
+        if enemy.health > 0.0 {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        ew.send(BossKilled {
+            def: boss.def,
+            position,
+        });
+        commands.entity(entity).despawn();
+        if let Some(def) = boss_defs.0.get(boss.def) {
+            drop_rewards(&mut commands, &handle, position, &def.rewards);
+        }
+    }
+}
+
+/// Spread evenly over the attack's arc, centered on `aim`.
+fn volley_directions(attack: &BossAttack, aim: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+    let count = attack.projectiles;
+    let spread = attack.spread_degrees.min(360.0).to_radians();
+    // A full ring would put the first and last projectile in the same spot.
+    let step = if attack.spread_degrees >= 360.0 {
+        spread / count as f32
+    } else {
+        spread / count.saturating_sub(1).max(1) as f32
+    };
+    let first = if count > 1 && attack.spread_degrees < 360.0 {
+        -spread / 2.0
+    } else {
+        0.0
+    };
+    (0..count).map(move |i| Vec2::from_angle(first + i as f32 * step).rotate(aim))
+}
+
+impl Boss {
+    fn new(def_index: usize, def: &BossDef, max_health: f32) -> Self {
+        let interval = def.phases[0]
+            .attack
+            .as_ref()
+            .map_or(0.0, |attack| attack.interval_secs);
+        let mut contact_cooldown = Timer::from_seconds(def.contact_interval_secs, TimerMode::Once);
+        contact_cooldown.tick(contact_cooldown.duration());
+        Self {
+            def: def_index,
+            max_health,
+            phase: 0,
+            attack_cooldown: Timer::from_seconds(interval, TimerMode::Once),
+            telegraph: None,
+            contact_cooldown,
+        }
+    }
+
+    pub fn current_phase<'a>(&self, boss_defs: &'a BossDefs) -> Option<&'a BossPhase> {
+        boss_defs.0.get(self.def)?.phases.get(self.phase)
+    }
+
+    pub fn is_telegraphing(&self) -> bool {
+        self.telegraph.is_some()
+    }
+}
@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::enemy::EnemyType;
+use crate::game_config::{non_negative, positive, ConfigError};
+use crate::pickup::Reward;
+use crate::ron_asset::{apply_ron_asset, RonAsset, RonAssetHandle, RonLoader};
+use crate::state::GameState;
+use crate::*;
+
+pub struct BossDefPlugin;
+
+/// One boss encounter. Each one shows up at most once per run.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BossDef {
+    pub name: String,
+    /// Borrows the sprite of this enemy type.
+    pub sprite: EnemyType,
+    /// Size relative to regular enemies, collider included.
+    pub scale: f32,
+    pub health: f32,
+    pub mass: f32,
+    /// Before sprite scaling.
+    pub collider_radius: f32,
+    /// Damage to the player per touch, at most once every `contact_interval_secs`.
+    pub contact_damage: f32,
+    pub contact_interval_secs: f32,
+    pub score: u32,
+    pub trigger: BossTrigger,
+    /// The first one starts at full health, the others take over in order.
+    pub phases: Vec<BossPhase>,
+    /// Dropped where it dies.
+    #[serde(default)]
+    pub rewards: Vec<Reward>,
+}
+
+/// When the boss shows up, once no other boss is alive.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum BossTrigger {
+    AfterSecs(f32),
+    AfterKills(u32),
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BossPhase {
+    /// Starts once health drops to this share of the maximum.
+    pub health_fraction: f32,
+    pub speed: f32,
+    pub movement: BossMovement,
+    #[serde(default)]
+    pub attack: Option<BossAttack>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum BossMovement {
+    Chase,
+    /// Circles the player at `distance`.
+    Orbit {
+        distance: f32,
+    },
+}
+
+/// Volleys of enemy projectiles, each one telegraphed.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BossAttack {
+    pub projectiles: u32,
+    /// Arc the volley covers, centered on the player. 360 fires a ring.
+    pub spread_degrees: f32,
+    pub interval_secs: f32,
+    /// Warning before each volley. The boss halts and flashes meanwhile.
+    pub telegraph_secs: f32,
+    pub projectile_speed: f32,
+    pub projectile_damage: f32,
+    pub projectile_lifetime_secs: f32,
+}
+
+/// Every boss, read from `assets/bosses.ron`.
+#[derive(Resource, Asset, TypePath, Deserialize, Debug, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct BossDefs(pub Vec<BossDef>);
+
+impl Plugin for BossDefPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<BossDefs>()
+            .register_asset_loader(RonLoader::<BossDefs>::default())
+            .add_systems(OnEnter(GameState::Loading), load_boss_defs)
+            .add_systems(
+                PreUpdate,
+                apply_ron_asset::<BossDefs>.run_if(resource_exists::<RonAssetHandle<BossDefs>>),
+            );
+    }
+}
+
+fn load_boss_defs(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(RonAssetHandle::<BossDefs>(
+        asset_server.load(BOSS_DEFS_PATH),
+    ));
+}
+
+impl RonAsset for BossDefs {
+    const EXTENSIONS: &'static [&'static str] = &["bosses.ron"];
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        for def in &self.0 {
+            positive("scale", def.scale)?;
+            positive("health", def.health)?;
+            positive("mass", def.mass)?;
+            positive("collider_radius", def.collider_radius)?;
+            non_negative("contact_damage", def.contact_damage)?;
+            positive("contact_interval_secs", def.contact_interval_secs)?;
+            if let BossTrigger::AfterSecs(secs) = def.trigger {
+                non_negative("trigger", secs)?;
+            }
+            def.validate_phases()?;
+        }
+        Ok(())
+    }
+}
+
+impl BossDef {
+    fn validate_phases(&self) -> Result<(), ConfigError> {
+        if self.phases.first().map(|phase| phase.health_fraction) != Some(1.0) {
+            return Err(ConfigError::Invalid {
+                key: "phases".into(),
+                reason: "must start with a phase at health_fraction 1.0".into(),
+            });
+        }
+        if self
+            .phases
+            .windows(2)
+            .any(|pair| pair[1].health_fraction >= pair[0].health_fraction)
+        {
+            return Err(ConfigError::Invalid {
+                key: "health_fraction".into(),
+                reason: "must go down from one phase to the next".into(),
+            });
+        }
+        for phase in &self.phases {
+            positive("health_fraction", phase.health_fraction)?;
+            non_negative("speed", phase.speed)?;
+            if let BossMovement::Orbit { distance } = phase.movement {
+                non_negative("distance", distance)?;
+            }
+            if let Some(attack) = &phase.attack {
+                positive("spread_degrees", attack.spread_degrees)?;
+                positive("interval_secs", attack.interval_secs)?;
+                non_negative("telegraph_secs", attack.telegraph_secs)?;
+                positive("projectile_speed", attack.projectile_speed)?;
+                non_negative("projectile_damage", attack.projectile_damage)?;
+                positive("projectile_lifetime_secs", attack.projectile_lifetime_secs)?;
+            }
+        }
+        Ok(())
+    }
+}
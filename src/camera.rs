@@ -1,14 +1,17 @@
 use bevy::{math::vec3, prelude::*};
-use bevy_pancam::{PanCam, PanCamPlugin};
+use bevy_pancam::{PanCam, PanCamSystemSet};
 
+use crate::input::ZOOM_MODIFIER_KEYS;
 use crate::player::Player;
 use crate::state::GameState;
+use crate::*;
 
 pub struct FollowCameraPlugin;
 
 impl Plugin for FollowCameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(PanCamPlugin::default())
+        // The mouse wheel switches weapons unless a zoom modifier is held.
+        app.configure_sets(Update, PanCamSystemSet.run_if(zoom_modifier_pressed))
             .add_systems(Startup, setup_camera)
             .add_systems(
                 Update,
@@ -17,6 +20,10 @@ impl Plugin for FollowCameraPlugin {
     }
 }
 
+fn zoom_modifier_pressed(keyboard_input: Res<ButtonInput<KeyCode>>) -> bool {
+    keyboard_input.any_pressed(ZOOM_MODIFIER_KEYS)
+}
+
 fn setup_camera(mut commands: Commands) {
     commands.spawn(Camera2dBundle::default()).insert(PanCam {
         // grab_buttons: vec![MouseButton::Middle],
@@ -28,6 +35,7 @@ fn setup_camera(mut commands: Commands) {
 }
 
 fn camera_follow_player(
+    time: Res<Time>,
     player_query: Query<&Transform, With<Player>>,
     mut camera_query: Query<&mut Transform, (With<Camera>, Without<Player>)>,
 ) {
@@ -39,5 +47,6 @@ fn camera_follow_player(
     let player_transform = player_query.single().translation;
     let (x, y) = (player_transform.x, player_transform.y);
 
-    camera_transform.translation = camera_transform.translation.lerp(vec3(x, y, 0.0), 0.1);
+    let t = 1.0 - (-CAMERA_FOLLOW_SHARPNESS * time.delta_seconds()).exp();
+    camera_transform.translation = camera_transform.translation.lerp(vec3(x, y, 0.0), t);
 }
@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+
+use crate::elite::{Elite, Haste};
+use crate::enemy_def::ChargerDef;
+use crate::game_config::GameConfig;
+use crate::player::Player;
+use crate::state::GameState;
+use crate::*;
+
+pub struct ChargerPlugin;
+
+/// Where a charger is in its wind-up, dash and cooldown cycle. Chasing in
+/// between is left to the regular enemy movement. Timings come from the
+/// `ChargerDef` it spawned with.
+#[derive(Component)]
+pub struct ChargerState {
+    def: ChargerDef,
+    phase: ChargePhase,
+}
+
+enum ChargePhase {
+    Ready,
+    WindUp(Timer),
+    Dash { dir: Vec2, timer: Timer },
+    Cooldown(Timer),
+}
+
+impl Plugin for ChargerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_chargers.run_if(in_state(GameState::InGame)));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_chargers(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    player_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<
+        (
+            &mut Transform,
+            &mut ChargerState,
+            &mut Sprite,
+            Option<&Elite>,
+            Option<&Haste>,
+        ),
+        Without<Player>,
+    >,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let player_pos = player_query.single().translation.truncate();
+    for (mut transform, mut state, mut sprite, elite, haste) in enemy_query.iter_mut() {
+        let ChargerState {
+            def: charger,
+            phase,
+        } = &mut *state;
+        let pos = transform.translation.truncate();
+
+        match phase {
+            ChargePhase::Ready => {
+                if pos.distance(player_pos) <= charger.trigger_range {
+                    let (r, g, b) = TELEGRAPH_COLOR;
+                    sprite.color = Color::srgb(r, g, b);
+                    *phase = ChargePhase::WindUp(Timer::from_seconds(
+                        charger.windup_secs,
+                        TimerMode::Once,
+                    ));
+                }
+            }
+            ChargePhase::WindUp(timer) => {
+                if timer.tick(time.delta()).finished() {
+                    sprite.color = elite.map_or(Color::WHITE, Elite::color);
+                    // Aims where the player is once the wind-up ends.
+                    *phase = ChargePhase::Dash {
+                        dir: (player_pos - pos).normalize_or_zero(),
+                        timer: Timer::from_seconds(charger.dash_secs, TimerMode::Once),
+                    };
+                }
+            }
+            ChargePhase::Dash { dir, timer } => {
+                let haste = haste.map_or(1.0, |haste| haste.0);
+                let speed = charger.dash_speed * config.enemy.speed_scale * haste;
+                transform.translation += (*dir * speed * time.delta_seconds()).extend(0.0);
+                if timer.tick(time.delta()).finished() {
+                    *phase = ChargePhase::Cooldown(Timer::from_seconds(
+                        charger.cooldown_secs,
+                        TimerMode::Once,
+                    ));
+                }
+            }
+            ChargePhase::Cooldown(timer) => {
+                if timer.tick(time.delta()).finished() {
+                    *phase = ChargePhase::Ready;
+                }
+            }
+        }
+    }
+}
+
+impl ChargerState {
+    pub fn new(def: &ChargerDef) -> Self {
+        Self {
+            def: def.clone(),
+            phase: ChargePhase::Ready,
+        }
+    }
+
+    /// Whether it is winding up or dashing, rather than chasing.
+    pub fn is_charging(&self) -> bool {
+        matches!(
+            self.phase,
+            ChargePhase::WindUp(_) | ChargePhase::Dash { .. }
+        )
+    }
+
+    pub fn is_dashing(&self) -> bool {
+        matches!(self.phase, ChargePhase::Dash { .. })
+    }
+}
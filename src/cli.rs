@@ -0,0 +1,76 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// Command line options, e.g. `infinite_shooter_game --seed 42 --record run.replay`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CliArgs {
+    pub seed: Option<u64>,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    MissingValue(&'static str),
+    InvalidValue { flag: &'static str, value: String },
+    UnknownArgument(String),
+    Conflict(&'static str, &'static str),
+}
+
+impl CliArgs {
+    pub fn parse() -> Result<Self, CliError> {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    pub fn parse_from(args: impl IntoIterator<Item = String>) -> Result<Self, CliError> {
+        let mut cli = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let value = args.next().ok_or(CliError::MissingValue("--seed"))?;
+                    let seed = value.parse().map_err(|_| CliError::InvalidValue {
+                        flag: "--seed",
+                        value,
+                    })?;
+                    cli.seed = Some(seed);
+                }
+                "--record" => {
+                    let value = args.next().ok_or(CliError::MissingValue("--record"))?;
+                    cli.record = Some(PathBuf::from(value));
+                }
+                "--replay" => {
+                    let value = args.next().ok_or(CliError::MissingValue("--replay"))?;
+                    cli.replay = Some(PathBuf::from(value));
+                }
+                _ => return Err(CliError::UnknownArgument(arg)),
+            }
+        }
+
+        if cli.replay.is_some() {
+            if cli.seed.is_some() {
+                return Err(CliError::Conflict("--replay", "--seed"));
+            }
+            if cli.record.is_some() {
+                return Err(CliError::Conflict("--replay", "--record"));
+            }
+        }
+
+        Ok(cli)
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::MissingValue(flag) => write!(f, "`{flag}` expects a value"),
+            CliError::InvalidValue { flag, value } => {
+                write!(f, "invalid value `{value}` for `{flag}`")
+            }
+            CliError::UnknownArgument(arg) => write!(f, "unknown argument `{arg}`"),
+            CliError::Conflict(a, b) => write!(f, "`{a}` cannot be used with `{b}`"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
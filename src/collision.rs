@@ -1,11 +1,20 @@
-use bevy::utils::Duration;
+use std::cmp::Ordering;
 
-use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
 use kd_tree::{KdPoint, KdTree};
 
+use crate::boss::Boss;
+use crate::elite::{Armor, LifeSteal};
+use crate::enemy::{DamageSource, Enemy, EnemyType};
+use crate::enemy_def::EnemyDefs;
+use crate::game_config::{on_config_timer, GameConfig};
+use crate::gun::{Bullet, Pierce};
 use crate::player::{Player, PlayerEnemyCollisionEvent};
+use crate::projectile::{release_projectile, Explosion, Explosive};
+use crate::state::GameState;
+use crate::stats::RunStats;
 use crate::*;
-use crate::{enemy::Enemy, gun::Bullet, state::GameState};
 
 pub struct CollisionPlugin;
 
@@ -15,25 +24,45 @@ struct Collidable {
     entity: Entity,
 }
 #[derive(Resource)]
-struct EnemyKdTree(KdTree<Collidable>);
+pub struct EnemyKdTree {
+    tree: KdTree<Collidable>,
+    /// Largest enemy collider radius when the tree was built.
+    max_radius: f32,
+}
+
+/// Sent when a bullet touches an enemy. Damage is applied when the event is handled.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct BulletHit {
+    pub bullet: Entity,
+    pub enemy: Entity,
+    pub damage: f32,
+    pub point: Vec2,
+}
 
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(EnemyKdTree::default()).add_systems(
-            Update,
-            (
-                handle_enemy_bullet_collision,
-                handle_enemy_player_collision,
-                update_enemy_kd_tree
-                    .run_if(on_timer(Duration::from_secs_f32(KD_TREE_REFRESH_RATE))),
-            )
-                .run_if(in_state(GameState::InGame)),
-        );
+        app.insert_resource(EnemyKdTree::default())
+            .add_event::<BulletHit>()
+            .add_systems(
+                Update,
+                (
+                    (handle_enemy_bullet_collision, apply_bullet_hits).chain(),
+                    handle_enemy_player_collision,
+                    update_enemy_kd_tree.run_if(on_config_timer(|config| {
+                        config.collision.kd_tree_refresh_rate
+                    })),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
     }
 }
 
 fn handle_enemy_player_collision(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    enemy_defs: Res<EnemyDefs>,
     player_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<(&EnemyType, &mut Enemy, Option<&LifeSteal>), Without<Boss>>,
     tree: Res<EnemyKdTree>,
     mut ew: EventWriter<PlayerEnemyCollisionEvent>,
 ) {
@@ -42,48 +71,130 @@ fn handle_enemy_player_collision(
     }
 
     let player_pos = player_query.single().translation;
-    let enemies = tree.0.within_radius(&[player_pos.x, player_pos.y], 50.0);
-    for _ in enemies.iter() {
-        ew.send(PlayerEnemyCollisionEvent);
+    let enemies = tree.tree.within_radius(&[player_pos.x, player_pos.y], 50.0);
+    for enemy in enemies.iter() {
+        let Ok((enemy_type, mut enemy, life_steal)) = enemy_query.get_mut(enemy.entity) else {
+            continue;
+        };
+        let damage =
+            enemy_defs.get(*enemy_type).damage * config.enemy.damage_scale * time.delta_seconds();
+        if let Some(life_steal) = life_steal {
+            let healed = enemy.health + damage * life_steal.per_damage;
+            enemy.health = healed.min(life_steal.max_health).max(enemy.health);
+        }
+        ew.send(PlayerEnemyCollisionEvent { damage });
     }
 }
 
 fn update_enemy_kd_tree(
     mut tree: ResMut<EnemyKdTree>,
-    enemy_query: Query<(&Transform, Entity), With<Enemy>>,
+    enemy_query: Query<(&Transform, &Collider, Entity), With<Enemy>>,
 ) {
     let mut items = Vec::new();
-    for (t, e) in enemy_query.iter() {
+    let mut max_radius: f32 = 0.0;
+    for (t, collider, e) in enemy_query.iter() {
         items.push(Collidable {
             entity: e,
             pos: t.translation.truncate(),
-        })
+        });
+        max_radius = max_radius.max(collider_radius(collider, t));
     }
 
-    tree.0 = KdTree::build_by_ordered_float(items);
+    tree.tree = KdTree::build_by_ordered_float(items);
+    tree.max_radius = max_radius;
 }
 
-fn handle_enemy_bullet_collision(
-    bullet_query: Query<&Transform, With<Bullet>>,
+/// Each bullet hits the closest enemy whose collider it touches, then moves
+/// on to the next one if it still has pierce left.
+#[allow(clippy::too_many_arguments)]
+pub fn handle_enemy_bullet_collision(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mut stats: ResMut<RunStats>,
+    mut bullet_query: Query<(&Transform, &Bullet, &mut Pierce, Option<&Explosive>, Entity)>,
     tree: Res<EnemyKdTree>,
-    mut enemy_query: Query<(&Transform, &mut Enemy), With<Enemy>>,
+    enemy_query: Query<(&Transform, &Collider), With<Enemy>>,
+    mut ew: EventWriter<BulletHit>,
+    mut explosions: EventWriter<Explosion>,
 ) {
     if bullet_query.is_empty() || enemy_query.is_empty() {
         return;
     }
 
-    for b_t in bullet_query.iter() {
-        let pos = b_t.translation;
-        let enemies = tree.0.within_radius(&[pos.x, pos.y], 50.0);
+    let bullet_radius = config.gun.bullet_radius;
+    let search_radius = tree.max_radius + bullet_radius + KD_TREE_QUERY_MARGIN;
+    let mut touching = Vec::new();
+    for (b_t, b, mut pierce, explosive, bullet) in bullet_query.iter_mut() {
+        let pos = b_t.translation.truncate();
+
+        touching.clear();
+        for e in tree.tree.within_radius(&[pos.x, pos.y], search_radius) {
+            if pierce.hit.contains(&e.entity) {
+                continue;
+            }
+            let Ok((e_t, collider)) = enemy_query.get(e.entity) else {
+                continue;
+            };
+
+            let enemy_pos = e_t.translation.truncate();
+            let radius = collider_radius(collider, e_t);
+            let dist = pos.distance(enemy_pos);
+            if dist <= radius + bullet_radius {
+                let point = enemy_pos + (pos - enemy_pos).normalize_or_zero() * radius;
+                touching.push((dist, e.entity, point));
+            }
+        }
+        touching.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
 
-        for e in enemies {
-            if let Ok((_, mut enemy)) = enemy_query.get_mut(e.entity) {
-                enemy.health -= BULLET_DAMAGE;
+        for &(_, enemy, point) in touching.iter() {
+            if pierce.hit.is_empty() {
+                stats.shots_hit += 1;
             }
+            pierce.hit.push(enemy);
+            ew.send(BulletHit {
+                bullet,
+                enemy,
+                damage: b.damage,
+                point,
+            });
+            if pierce.remaining == 0 {
+                if let Some(explosive) = explosive {
+                    explosions.send(Explosion {
+                        position: point,
+                        radius: explosive.radius,
+                        damage: explosive.damage,
+                    });
+                }
+                release_projectile(&mut commands, bullet);
+                break;
+            }
+            pierce.remaining -= 1;
+        }
+    }
+}
+
+pub fn apply_bullet_hits(
+    mut enemy_query: Query<(&mut Enemy, Option<&Armor>)>,
+    mut events: EventReader<BulletHit>,
+) {
+    for hit in events.read() {
+        if let Ok((mut enemy, armor)) = enemy_query.get_mut(hit.enemy) {
+            enemy.health -= armor.map_or(hit.damage, |armor| armor.reduce(hit.damage));
+            enemy.last_hit_by = Some(DamageSource::Bullet);
         }
     }
 }
 
+/// Radius of the circle bounding `collider`, scaled like its entity.
+pub(crate) fn collider_radius(collider: &Collider, transform: &Transform) -> f32 {
+    let unscaled_radius = match collider.as_unscaled_typed_shape() {
+        ColliderView::Ball(ball) => ball.radius(),
+        _ => collider.raw.compute_local_bounding_sphere().radius() / collider.scale().max_element(),
+    };
+
+    unscaled_radius * transform.scale.truncate().abs().max_element()
+}
+
 impl KdPoint for Collidable {
     type Scalar = f32;
     type Dim = typenum::U2;
@@ -96,8 +207,28 @@ impl KdPoint for Collidable {
     }
 }
 
+impl EnemyKdTree {
+    /// Enemies whose position at the last refresh is within `radius` of `pos`.
+    pub fn within_radius(&self, pos: Vec2, radius: f32) -> impl Iterator<Item = Entity> + '_ {
+        self.tree
+            .within_radius(&[pos.x, pos.y], radius)
+            .into_iter()
+            .map(|collidable| collidable.entity)
+    }
+
+    pub fn nearest_within(&self, pos: Vec2, radius: f32) -> Option<Entity> {
+        self.tree
+            .nearest(&[pos.x, pos.y])
+            .filter(|nearest| nearest.squared_distance <= radius * radius)
+            .map(|nearest| nearest.item.entity)
+    }
+}
+
 impl Default for EnemyKdTree {
     fn default() -> Self {
-        Self(KdTree::build_by_ordered_float(vec![]))
+        Self {
+            tree: KdTree::build_by_ordered_float(vec![]),
+            max_radius: 0.0,
+        }
     }
 }
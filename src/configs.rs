@@ -12,6 +12,40 @@ pub const TILE_H: u32 = 16;
 pub const SPRITE_SHEET_W: u32 = 8;
 pub const SPRITE_SHEET_H: u32 = 8;
 
+// Camera, how quickly it catches up with the player (per second)
+pub const CAMERA_FOLLOW_SHARPNESS: f32 = 6.3;
+
+// Gamepad
+// Stick push, from 0 to 1, that makes the gamepad the active device
+pub const GAMEPAD_ACTIVATION_THRESHOLD: f32 = 0.5;
+pub const GAMEPAD_MOVE_DEADZONE: f32 = 0.15;
+pub const GAMEPAD_AIM_DEADZONE: f32 = 0.3;
+// Distance from the player of the point the right stick aims at
+pub const GAMEPAD_AIM_DISTANCE: f32 = 200.0;
+
+// Headless
+pub const HEADLESS_TIMESTEP_SECS: f32 = 1.0 / 60.0;
+pub const HEADLESS_STARTUP_TIMEOUT_SECS: u64 = 10;
+
+// Game config, relative to the assets folder
+pub const GAME_CONFIG_PATH: &str = "game.config.ron";
+
+// Weapon definitions, loaded from `<WEAPONS_DIR>/<name>.weapon.ron` in the assets folder
+pub const WEAPONS_DIR: &str = "weapons";
+pub const BUILT_IN_WEAPONS: [&str; 4] = ["pistol", "shotgun", "smg", "sniper"];
+
+// Enemy types' stats, relative to the assets folder
+pub const ENEMY_DEFS_PATH: &str = "enemies.ron";
+pub const BOSS_DEFS_PATH: &str = "bosses.ron";
+
+// High scores, stored in the user's data directory
+pub const HIGH_SCORES_DIR_NAME: &str = "infinite_shooter_game";
+pub const HIGH_SCORES_FILE_NAME: &str = "high_scores.ron";
+pub const NUM_HIGH_SCORES: usize = 10;
+
+// Defaults for the values below can be overridden in the game config file.
+// Speeds are in world units per second.
+
 // World
 pub const NUM_WORLD_DECORATIONS: usize = 50;
 pub const NUM_WORLD_TREES: usize = 20;
@@ -19,32 +53,100 @@ pub const WORLD_W: f32 = 3000.0;
 pub const WORLD_H: f32 = 2500.0;
 
 // Player
-// pub const PLAYER_SPEED: f32 = 2.0;
-pub const PLAYER_SPEED: f32 = 2.0;
+// pub const PLAYER_SPEED: f32 = 120.0;
+pub const PLAYER_SPEED: f32 = 120.0;
 pub const PLAYER_HEALTH: f32 = 100.0;
 
 // Enemy
 // pub const MAX_NUM_ENEMIES: usize = 20000;
 pub const MAX_NUM_ENEMIES: usize = 1000;
-pub const ENEMY_DAMAGE: f32 = 1.0;
 pub const SPAWN_RATE_PER_SECOND: usize = 500;
-// pub const ENEMY_HEALTH: f32 = 100.0;
-pub const ENEMY_HEALTH: f32 = 500.0;
 pub const ENEMY_SPAWN_INTERVAL: f32 = 1.0;
-pub const ENEMY_SPEED: f32 = 1.0;
+// Applied on top of every enemy type's own stats
+pub const ENEMY_HEALTH_SCALE: f32 = 1.0;
+pub const ENEMY_SPEED_SCALE: f32 = 1.0;
+pub const ENEMY_DAMAGE_SCALE: f32 = 1.0;
+// Farthest a pack member spawns from the first one
+pub const PACK_SPREAD: f32 = 60.0;
+// Distance from a dead splitter its offspring appear at
+pub const SPLIT_SPREAD: f32 = 20.0;
+// Shots past it are skipped until some of them are gone
+pub const MAX_ENEMY_PROJECTILES: usize = 200;
+
+// Ranged enemies
+// Distance from the preferred one they don't bother correcting
+pub const RANGED_DISTANCE_TOLERANCE: f32 = 40.0;
+pub const ENEMY_PROJECTILE_RADIUS: f32 = 6.0;
+pub const ENEMY_PROJECTILE_SPRITE_INDEX: usize = 8;
+
+// Elites
+pub const ELITE_BASE_CHANCE: f32 = 0.0;
+pub const ELITE_CHANCE_PER_MINUTE: f32 = 0.02;
+pub const ELITE_MAX_CHANCE: f32 = 0.15;
+pub const ELITE_AFFIXES: usize = 1;
+pub const ELITE_ARMOR: f32 = 15.0;
+// Armored elites still take this share of every hit
+pub const ARMOR_MIN_DAMAGE_FRACTION: f32 = 0.1;
+pub const ELITE_HASTE: f32 = 1.6;
+pub const ELITE_REGENERATION: f32 = 0.05;
+pub const ELITE_LIFE_STEAL: f32 = 2.0;
+pub const ELITE_EXPLOSION_RADIUS: f32 = 120.0;
+pub const ELITE_EXPLOSION_DAMAGE: f32 = 15.0;
+pub const ELITE_HEALTH_REWARD: f32 = 10.0;
+pub const ELITE_SCORE_REWARD: u32 = 50;
+
+// Bosses
+pub const BOSS_SPAWN_DISTANCE: f32 = 900.0;
+// Gap between a boss and the player that still counts as touching
+pub const BOSS_CONTACT_MARGIN: f32 = 5.0;
+
+// Pickups
+pub const PICKUP_RADIUS: f32 = 40.0;
+pub const PICKUP_DROP_SPREAD: f32 = 50.0;
+pub const PICKUP_SPRITE_INDEX: usize = 8;
+// Relative to other sprites
+pub const PICKUP_SCALE: f32 = 1.5;
 
 // Kd-tree
 pub const KD_TREE_REFRESH_RATE: f32 = 0.1;
+// Extra search radius covering enemy movement between kd-tree refreshes
+pub const KD_TREE_QUERY_MARGIN: f32 = 50.0;
 
-// Gun
-pub const BULLET_SPAWN_INTERVAL: f32 = 0.1;
-pub const BULLET_TIME_SECS: f32 = 0.5;
-pub const BULLET_SPEED: f32 = 15.0;
-// pub const BULLET_DAMAGE: f32 = 15.0;
-pub const BULLET_DAMAGE: f32 = 1.0;
+// Projectiles
+// Distance from the surface a bouncing projectile restarts at
+pub const RICOCHET_OFFSET: f32 = 0.5;
+// Hidden projectile entities kept for reuse
+pub const PROJECTILE_POOL_SIZE: usize = 2048;
+// Largest projectile_pool_size a config may ask for, since it is allocated up front
+pub const MAX_PROJECTILE_POOL_SIZE: usize = 65536;
+
+// Score
+pub const COMBO_WINDOW_SECS: f32 = 1.5;
+pub const COMBO_KILLS_PER_STEP: u32 = 10;
+pub const MAX_COMBO_MULTIPLIER: u32 = 5;
 
-// pub const NUM_BULLETS_PER_SHOT: usize = 10;
-pub const NUM_BULLETS_PER_SHOT: usize = 100;
+// Aim
+pub const AIM_RANGE: f32 = 600.0;
+pub const AIM_ASSIST_CONE_DEGREES: f32 = 30.0;
+pub const AIM_ASSIST_STRENGTH: f32 = 0.5;
+
+// Gun
+// Weapons the player carries at the start of a run, the first one equipped
+pub const STARTING_WEAPONS: [&str; 4] = ["shotgun", "pistol", "smg", "sniper"];
+// One per number key
+pub const MAX_WEAPON_SLOTS: usize = 9;
+pub const BULLET_RADIUS: f32 = 4.0;
 
 // Colors
 pub const BG_COLOR: (f32, f32, f32) = (0.314, 0.608, 0.4);
+pub const TELEGRAPH_COLOR: (f32, f32, f32) = (1.0, 0.35, 0.35);
+pub const ENEMY_PROJECTILE_COLOR: (f32, f32, f32) = (1.0, 0.2, 0.6);
+pub const HEALTH_PICKUP_COLOR: (f32, f32, f32) = (0.3, 1.0, 0.3);
+pub const AMMO_PICKUP_COLOR: (f32, f32, f32) = (1.0, 0.85, 0.2);
+pub const SCORE_PICKUP_COLOR: (f32, f32, f32) = (0.3, 0.8, 1.0);
+pub const ARMORED_COLOR: (f32, f32, f32) = (0.55, 0.65, 1.0);
+pub const HASTED_COLOR: (f32, f32, f32) = (1.0, 1.0, 0.35);
+pub const REGENERATING_COLOR: (f32, f32, f32) = (0.4, 1.0, 0.4);
+pub const VAMPIRIC_COLOR: (f32, f32, f32) = (0.75, 0.3, 1.0);
+pub const VOLATILE_COLOR: (f32, f32, f32) = (1.0, 0.6, 0.2);
+pub const BOSS_BAR_COLOR: (f32, f32, f32) = (0.8, 0.1, 0.1);
@@ -0,0 +1,194 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy::utils::Duration;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::enemy::{despawn_dead_enemies, Enemy, EnemyDeathSet};
+use crate::game_config::{EliteConfig, GameConfig};
+use crate::pickup::drop_rewards;
+use crate::player::{Player, PlayerEnemyCollisionEvent};
+use crate::state::GameState;
+use crate::*;
+
+pub struct ElitePlugin;
+
+/// A regular enemy rolled as elite when it spawned. Each affix adds its own
+/// component, and the first one picks the tint.
+#[derive(Component, Debug, Clone)]
+pub struct Elite {
+    pub affixes: Vec<EliteAffix>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EliteAffix {
+    Armored,
+    Hasted,
+    Regenerating,
+    Vampiric,
+    Volatile,
+}
+
+/// Taken off every hit, down to `ARMOR_MIN_DAMAGE_FRACTION` of it.
+#[derive(Component)]
+pub struct Armor(pub f32);
+
+/// Movement speed multiplier.
+#[derive(Component)]
+pub struct Haste(pub f32);
+
+#[derive(Component)]
+pub struct Regeneration {
+    pub per_sec: f32,
+    pub max_health: f32,
+}
+
+/// Heals `per_damage` for each point of contact damage dealt to the player.
+#[derive(Component)]
+pub struct LifeSteal {
+    pub per_damage: f32,
+    pub max_health: f32,
+}
+
+/// Hurts the player if they are within `radius` when the enemy dies.
+#[derive(Component)]
+pub struct DeathExplosion {
+    pub radius: f32,
+    pub damage: f32,
+}
+
+impl Plugin for ElitePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                regenerate_elites,
+                handle_dying_elites
+                    .in_set(EnemyDeathSet)
+                    .before(despawn_dead_enemies),
+            )
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+fn regenerate_elites(time: Res<Time>, mut enemy_query: Query<(&mut Enemy, &Regeneration)>) {
+    for (mut enemy, regeneration) in enemy_query.iter_mut() {
+        if enemy.health <= 0.0 {
+            continue;
+        }
+
+        let healed = enemy.health + regeneration.per_sec * time.delta_seconds();
+        enemy.health = healed.min(regeneration.max_health).max(enemy.health);
+    }
+}
+
+fn handle_dying_elites(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    handle: Res<GlobalTextureAtlas>,
+    player_query: Query<&Transform, With<Player>>,
+    elite_query: Query<(&Enemy, &Transform, Option<&DeathExplosion>), With<Elite>>,
+    mut ew: EventWriter<PlayerEnemyCollisionEvent>,
+) {
+    let player_pos = player_query
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation.truncate());
+    for (enemy, transform, explosion) in elite_query.iter() {
+        if enemy.health > 0.0 {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        drop_rewards(&mut commands, &handle, position, &config.elite.rewards);
+        let Some(explosion) = explosion else {
+            continue;
+        };
+        if player_pos.is_some_and(|player_pos| player_pos.distance(position) <= explosion.radius) {
+            ew.send(PlayerEnemyCollisionEvent {
+                damage: explosion.damage * config.enemy.damage_scale,
+            });
+        }
+    }
+}
+
+/// Goes from `base_chance` to `max_chance` at `chance_per_minute`.
+pub fn elite_chance(config: &EliteConfig, survival_time: Duration) -> f32 {
+    let minutes = survival_time.as_secs_f32() / 60.0;
+    (config.base_chance + config.chance_per_minute * minutes)
+        .min(config.max_chance)
+        .clamp(0.0, 1.0)
+}
+
+/// Rolls `config.affixes` different affixes and adds them to a freshly
+/// spawned enemy with `max_health`.
+pub fn make_elite(
+    enemy: &mut EntityCommands,
+    config: &EliteConfig,
+    rng: &mut impl Rng,
+    max_health: f32,
+) {
+    let affixes: Vec<EliteAffix> = EliteAffix::ALL
+        .choose_multiple(rng, config.affixes)
+        .copied()
+        .collect();
+    for affix in &affixes {
+        match affix {
+            EliteAffix::Armored => enemy.insert(Armor(config.armor)),
+            EliteAffix::Hasted => enemy.insert(Haste(config.haste)),
+            EliteAffix::Regenerating => enemy.insert(Regeneration {
+                per_sec: config.regeneration * max_health,
+                max_health,
+            }),
+            EliteAffix::Vampiric => enemy.insert(LifeSteal {
+                per_damage: config.life_steal,
+                max_health,
+            }),
+            EliteAffix::Volatile => enemy.insert(DeathExplosion {
+                radius: config.explosion_radius,
+                damage: config.explosion_damage,
+            }),
+        };
+    }
+
+    let elite = Elite { affixes };
+    enemy.insert((
+        Sprite {
+            color: elite.color(),
+            ..default()
+        },
+        elite,
+    ));
+}
+
+impl Elite {
+    /// Its sprite's resting color.
+    pub fn color(&self) -> Color {
+        let (r, g, b) = match self.affixes.first() {
+            Some(EliteAffix::Armored) => ARMORED_COLOR,
+            Some(EliteAffix::Hasted) => HASTED_COLOR,
+            Some(EliteAffix::Regenerating) => REGENERATING_COLOR,
+            Some(EliteAffix::Vampiric) => VAMPIRIC_COLOR,
+            Some(EliteAffix::Volatile) => VOLATILE_COLOR,
+            None => return Color::WHITE,
+        };
+        Color::srgb(r, g, b)
+    }
+}
+
+impl EliteAffix {
+    pub const ALL: [Self; 5] = [
+        Self::Armored,
+        Self::Hasted,
+        Self::Regenerating,
+        Self::Vampiric,
+        Self::Volatile,
+    ];
+}
+
+impl Armor {
+    pub fn reduce(&self, damage: f32) -> f32 {
+        (damage - self.0).max(damage * ARMOR_MIN_DAMAGE_FRACTION)
+    }
+}
@@ -1,13 +1,24 @@
-use bevy::utils::Duration;
 use std::f32::consts::PI;
 
-use bevy::math::vec3;
-use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy::ecs::system::EntityCommands;
+use bevy::math::vec2;
+use bevy::prelude::*;
 use rand::Rng;
+use serde::Deserialize;
 
 use crate::animation::AnimationTimer;
+use crate::boss::Boss;
+use crate::charger::ChargerState;
+use crate::collision::apply_bullet_hits;
+use crate::elite::{elite_chance, make_elite, Haste};
+use crate::enemy_def::{EnemyBehaviour, EnemyDefs};
+use crate::game_config::{on_config_timer, GameConfig};
 use crate::player::Player;
+use crate::projectile::apply_explosions;
+use crate::ranged::RangedState;
+use crate::rng::{GameRng, RngStream};
 use crate::state::GameState;
+use crate::stats::RunStats;
 use crate::world::GameEntity;
 use crate::*;
 
@@ -18,9 +29,29 @@ pub struct EnemyPlugin;
 #[derive(Component)]
 pub struct Enemy {
     pub health: f32,
+    pub last_hit_by: Option<DamageSource>,
 }
 
-#[derive(Component)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageSource {
+    Bullet,
+    Explosion,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct EnemyKilled {
+    pub enemy_type: EnemyType,
+    pub position: Vec2,
+    pub by: Option<DamageSource>,
+}
+
+/// Systems that handle enemies killed this frame; they run after bullet and
+/// explosion damage is applied.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnemyDeathSet;
+
+/// Picks the sprite, and the stats in `EnemyDefs`.
+#[derive(Component, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum EnemyType {
     Green,
     Red,
@@ -30,89 +61,217 @@ pub enum EnemyType {
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                spawn_enemies.run_if(on_timer(Duration::from_secs_f32(ENEMY_SPAWN_INTERVAL))),
-                update_enemies_movements,
-                despawn_dead_enemies,
+        app.add_event::<EnemyKilled>()
+            .configure_sets(
+                Update,
+                EnemyDeathSet
+                    .after(apply_bullet_hits)
+                    .after(apply_explosions),
             )
-                .run_if(in_state(GameState::InGame)),
-        );
+            .add_systems(
+                Update,
+                (
+                    spawn_enemies.run_if(on_config_timer(|config| config.enemy.spawn_interval)),
+                    update_enemies_movements,
+                    despawn_dead_enemies.in_set(EnemyDeathSet),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
     }
 }
 
-fn despawn_dead_enemies(mut commands: Commands, enemy_query: Query<(&Enemy, Entity), With<Enemy>>) {
+#[allow(clippy::too_many_arguments)]
+pub fn despawn_dead_enemies(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    enemy_defs: Res<EnemyDefs>,
+    mut rng: ResMut<GameRng>,
+    handle: Res<GlobalTextureAtlas>,
+    enemy_query: Query<(&Enemy, &EnemyType, &Transform, Entity), Without<Boss>>,
+    all_enemies_query: Query<(), With<Enemy>>,
+    mut ew: EventWriter<EnemyKilled>,
+) {
     if enemy_query.is_empty() {
         return;
     }
 
-    for (enemy, entity) in enemy_query.iter() {
+    let rng = rng.stream(RngStream::Enemies);
+    let mut num_enemies = all_enemies_query.iter().len();
+    for (enemy, enemy_type, transform, entity) in enemy_query.iter() {
         if enemy.health <= 0.0 {
+            let position = transform.translation.truncate();
+            ew.send(EnemyKilled {
+                enemy_type: *enemy_type,
+                position,
+                by: enemy.last_hit_by,
+            });
             commands.entity(entity).despawn();
+            num_enemies -= 1;
+
+            let Some(split) = &enemy_defs.get(*enemy_type).split else {
+                continue;
+            };
+            // Offspring only take up the room left under the enemy cap.
+            let room = config.enemy.max_num_enemies.saturating_sub(num_enemies);
+            let count = (split.count as usize).min(room);
+            if count == 0 {
+                continue;
+            }
+            num_enemies += count;
+            let start_angle = rng.gen_range(0.0..PI * 2.0);
+            for i in 0..count {
+                let angle = start_angle + i as f32 * PI * 2.0 / count as f32;
+                spawn_enemy(
+                    &mut commands,
+                    &config,
+                    &enemy_defs,
+                    &handle,
+                    rng,
+                    split.into,
+                    position + Vec2::from_angle(angle) * SPLIT_SPREAD,
+                    split.scale,
+                );
+            }
         }
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn update_enemies_movements(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    enemy_defs: Res<EnemyDefs>,
     player_query: Query<&Transform, With<Player>>,
-    mut enemy_query: Query<&mut Transform, (With<Enemy>, Without<Player>)>,
+    mut enemy_query: Query<
+        (
+            &mut Transform,
+            &EnemyType,
+            Option<&ChargerState>,
+            Option<&Haste>,
+        ),
+        (Without<Player>, Without<RangedState>, Without<Boss>),
+    >,
 ) {
     if player_query.is_empty() || enemy_query.is_empty() {
         return;
     }
 
     let player_pos = player_query.single().translation;
-    for mut transform in enemy_query.iter_mut() {
+    for (mut transform, enemy_type, charger, haste) in enemy_query.iter_mut() {
+        if charger.is_some_and(ChargerState::is_charging) {
+            continue;
+        }
         let dir = (player_pos - transform.translation).normalize();
-        transform.translation += dir * ENEMY_SPEED;
+        let haste = haste.map_or(1.0, |haste| haste.0);
+        let speed = enemy_defs.get(*enemy_type).speed * config.enemy.speed_scale * haste;
+        transform.translation += dir * speed * time.delta_seconds();
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_enemies(
     mut commands: Commands,
+    config: Res<GameConfig>,
+    enemy_defs: Res<EnemyDefs>,
+    stats: Res<RunStats>,
+    mut rng: ResMut<GameRng>,
     handle: Res<GlobalTextureAtlas>,
     player_query: Query<&Transform, With<Player>>,
     enemy_query: Query<&Transform, (With<Enemy>, Without<Player>)>,
 ) {
     let num_enemies = enemy_query.iter().len();
-    let enemy_spawn_count = (MAX_NUM_ENEMIES - num_enemies).min(SPAWN_RATE_PER_SECOND);
-
-    if num_enemies >= MAX_NUM_ENEMIES || player_query.is_empty() {
+    let max_num_enemies = config.enemy.max_num_enemies;
+    if num_enemies >= max_num_enemies || player_query.is_empty() {
         return;
     }
 
+    let enemy_spawn_count = (max_num_enemies - num_enemies).min(config.enemy.spawn_rate_per_second);
+
     let player_pos = player_query.single().translation.truncate();
-    for _ in 0..enemy_spawn_count {
-        let (x, y) = get_random_position_around(player_pos);
-        let enemy_type = EnemyType::get_rand_enemy();
-        commands.spawn((
-            SpriteBundle {
-                texture: handle.image.clone().unwrap(),
-                transform: Transform::from_translation(vec3(x, y, 1.0))
-                    .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
-                ..default()
-            },
-            TextureAtlas {
-                layout: handle.layout.clone().unwrap(),
-                index: enemy_type.get_base_sprite_index(),
-            },
-            Enemy::default(),
-            enemy_type,
-            AnimationTimer(Timer::from_seconds(0.08, TimerMode::Repeating)),
-            GameEntity,
-            RigidBody::Dynamic,
-            Collider::ball(8.0),
-            LockedAxes::ROTATION_LOCKED,
-            GravityScale(0.0),
-            ColliderMassProperties::Density(1.0),
-            AdditionalMassProperties::Mass(100.0),
-        ));
+    let elite_chance = elite_chance(&config.elite, stats.survival_time);
+    let rng = rng.stream(RngStream::Enemies);
+    let mut spawned = 0;
+    while spawned < enemy_spawn_count {
+        let (x, y) = get_random_position_around(rng, player_pos);
+        let enemy_type = enemy_defs.random_type(rng);
+        let pack_size = enemy_defs.get(enemy_type).pack_size as usize;
+        let pack_size = pack_size.min(enemy_spawn_count - spawned);
+        for i in 0..pack_size {
+            let offset = if i == 0 {
+                Vec2::ZERO
+            } else {
+                Vec2::from_angle(rng.gen_range(0.0..PI * 2.0)) * rng.gen_range(0.0..PACK_SPREAD)
+            };
+            let mut enemy = spawn_enemy(
+                &mut commands,
+                &config,
+                &enemy_defs,
+                &handle,
+                rng,
+                enemy_type,
+                vec2(x, y) + offset,
+                1.0,
+            );
+            if rng.gen_bool(elite_chance as f64) {
+                let max_health = enemy_defs.get(enemy_type).health * config.enemy.health_scale;
+                make_elite(&mut enemy, &config.elite, rng, max_health);
+            }
+        }
+        spawned += pack_size;
+    }
+}
+
+/// `scale` shrinks or grows the enemy, collider included.
+#[allow(clippy::too_many_arguments)]
+fn spawn_enemy<'a>(
+    commands: &'a mut Commands,
+    config: &GameConfig,
+    enemy_defs: &EnemyDefs,
+    handle: &GlobalTextureAtlas,
+    rng: &mut impl Rng,
+    enemy_type: EnemyType,
+    position: Vec2,
+    scale: f32,
+) -> EntityCommands<'a> {
+    let def = enemy_defs.get(enemy_type);
+    let mut enemy = commands.spawn((
+        SpriteBundle {
+            texture: handle.image.clone().unwrap(),
+            transform: Transform::from_translation(position.extend(1.0))
+                .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR * scale)),
+            ..default()
+        },
+        TextureAtlas {
+            layout: handle.layout.clone().unwrap(),
+            index: enemy_type.get_base_sprite_index(),
+        },
+        Enemy {
+            health: def.health * config.enemy.health_scale,
+            last_hit_by: None,
+        },
+        enemy_type,
+        AnimationTimer(Timer::from_seconds(0.08, TimerMode::Repeating)),
+        GameEntity,
+        RigidBody::Dynamic,
+        Collider::ball(def.collider_radius),
+        LockedAxes::ROTATION_LOCKED,
+        GravityScale(0.0),
+        ColliderMassProperties::Density(1.0),
+        AdditionalMassProperties::Mass(def.mass),
+    ));
+    match &def.behaviour {
+        EnemyBehaviour::Chase => {}
+        EnemyBehaviour::Ranged(ranged) => {
+            enemy.insert(RangedState::new(ranged, def.speed, rng));
+        }
+        EnemyBehaviour::Charger(charger) => {
+            enemy.insert(ChargerState::new(charger));
+        }
     }
+    enemy
 }
 
-fn get_random_position_around(pos: Vec2) -> (f32, f32) {
-    let mut rng = rand::thread_rng();
+fn get_random_position_around(rng: &mut impl Rng, pos: Vec2) -> (f32, f32) {
     let angle = rng.gen_range(0.0..PI * 2.0);
     let dist = rng.gen_range(1000.0..5000.0);
 
@@ -125,25 +284,8 @@ fn get_random_position_around(pos: Vec2) -> (f32, f32) {
     (random_x, random_y)
 }
 
-impl Default for Enemy {
-    fn default() -> Self {
-        Self {
-            health: ENEMY_HEALTH,
-        }
-    }
-}
-
 impl EnemyType {
-    fn get_rand_enemy() -> Self {
-        let mut rng = rand::thread_rng();
-        let rand_index = rng.gen_range(0..4);
-        return match rand_index {
-            0 => Self::Green,
-            1 => Self::Red,
-            2 => Self::Skin,
-            _ => Self::White,
-        };
-    }
+    pub const ALL: [Self; 4] = [Self::Green, Self::Red, Self::Skin, Self::White];
 
     pub fn get_base_sprite_index(&self) -> usize {
         match self {
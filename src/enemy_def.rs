@@ -0,0 +1,214 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::enemy::EnemyType;
+use crate::game_config::{non_negative, positive, ConfigError};
+use crate::ron_asset::{apply_ron_asset, RonAsset, RonAssetHandle, RonLoader};
+use crate::state::GameState;
+use crate::*;
+
+pub struct EnemyDefPlugin;
+
+/// Stats of one `EnemyType`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct EnemyDef {
+    pub health: f32,
+    pub speed: f32,
+    /// Damage to the player per second of contact.
+    pub damage: f32,
+    pub mass: f32,
+    /// Before sprite scaling.
+    pub collider_radius: f32,
+    /// Points per kill, before the combo multiplier.
+    pub score: u32,
+    /// Chance of spawning relative to the other types.
+    pub spawn_weight: u32,
+    #[serde(default)]
+    pub behaviour: EnemyBehaviour,
+    /// Enemies left behind when it dies.
+    #[serde(default)]
+    pub split: Option<SplitDef>,
+    /// Enemies spawned together in one spot.
+    #[serde(default = "default_pack_size")]
+    pub pack_size: u32,
+}
+
+fn default_pack_size() -> u32 {
+    1
+}
+
+/// How an enemy type moves and attacks. Fixed for each enemy when it spawns.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub enum EnemyBehaviour {
+    /// Walks straight at the player.
+    #[default]
+    Chase,
+    Ranged(RangedDef),
+    Charger(ChargerDef),
+}
+
+/// Keeps its distance from the player and shoots at them.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RangedDef {
+    pub preferred_distance: f32,
+    /// Speed of circling the player while at the preferred distance.
+    pub strafe_speed: f32,
+    /// Only shoots while the player is this close.
+    pub fire_range: f32,
+    pub fire_interval_secs: f32,
+    /// Warning before each shot, while the enemy holds its position.
+    pub telegraph_secs: f32,
+    pub projectile_speed: f32,
+    pub projectile_damage: f32,
+    pub projectile_lifetime_secs: f32,
+}
+
+/// Chases the player, and dashes at them in a straight line once close.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ChargerDef {
+    /// Starts winding up once the player is this close.
+    pub trigger_range: f32,
+    /// Pause before each dash, giving the player time to step aside.
+    pub windup_secs: f32,
+    pub dash_speed: f32,
+    pub dash_secs: f32,
+    /// Chasing normally after a dash before the next wind-up.
+    pub cooldown_secs: f32,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SplitDef {
+    /// Can't split again.
+    pub into: EnemyType,
+    /// Fewer when `max_num_enemies` leaves less room.
+    pub count: u32,
+    /// Size relative to a normally spawned `into` enemy.
+    pub scale: f32,
+}
+
+/// Every enemy type's stats, read from `assets/enemies.ron`.
+/// Edits to the file apply to living enemies right away, except for the ones
+/// only used when spawning.
+#[derive(Resource, Asset, TypePath, Deserialize, Debug, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct EnemyDefs(HashMap<EnemyType, EnemyDef>);
+
+impl Plugin for EnemyDefPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<EnemyDefs>()
+            .register_asset_loader(RonLoader::<EnemyDefs>::default())
+            .add_systems(OnEnter(GameState::Loading), load_enemy_defs)
+            .add_systems(
+                PreUpdate,
+                apply_ron_asset::<EnemyDefs>.run_if(resource_exists::<RonAssetHandle<EnemyDefs>>),
+            );
+    }
+}
+
+fn load_enemy_defs(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(RonAssetHandle::<EnemyDefs>(
+        asset_server.load(ENEMY_DEFS_PATH),
+    ));
+}
+
+impl EnemyDefs {
+    pub fn get(&self, enemy_type: EnemyType) -> &EnemyDef {
+        &self.0[&enemy_type]
+    }
+
+    /// Picks a type with probability proportional to its spawn weight.
+    pub fn random_type(&self, rng: &mut impl Rng) -> EnemyType {
+        let total: u32 = EnemyType::ALL
+            .iter()
+            .map(|&enemy_type| self.get(enemy_type).spawn_weight)
+            .sum();
+        let mut roll = rng.gen_range(0..total);
+        for enemy_type in EnemyType::ALL {
+            let weight = self.get(enemy_type).spawn_weight;
+            if roll < weight {
+                return enemy_type;
+            }
+            roll -= weight;
+        }
+        unreachable!("roll is below the total weight")
+    }
+}
+
+impl RonAsset for EnemyDefs {
+    const EXTENSIONS: &'static [&'static str] = &["enemies.ron"];
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if !EnemyType::ALL
+            .iter()
+            .all(|enemy_type| self.0.contains_key(enemy_type))
+        {
+            return Err(ConfigError::Invalid {
+                key: "enemy types".into(),
+                reason: "must all be defined".into(),
+            });
+        }
+        for def in self.0.values() {
+            positive("health", def.health)?;
+            non_negative("speed", def.speed)?;
+            non_negative("damage", def.damage)?;
+            positive("mass", def.mass)?;
+            positive("collider_radius", def.collider_radius)?;
+            match &def.behaviour {
+                EnemyBehaviour::Chase => {}
+                EnemyBehaviour::Ranged(ranged) => ranged.validate()?,
+                EnemyBehaviour::Charger(charger) => charger.validate()?,
+            }
+            if let Some(split) = &def.split {
+                positive("split.scale", split.scale)?;
+                if self.get(split.into).split.is_some() {
+                    return Err(ConfigError::Invalid {
+                        key: "split.into".into(),
+                        reason: "must be a type that doesn't split".into(),
+                    });
+                }
+            }
+            if def.pack_size == 0 {
+                return Err(ConfigError::Invalid {
+                    key: "pack_size".into(),
+                    reason: "must be greater than 0".into(),
+                });
+            }
+        }
+        if self.0.values().all(|def| def.spawn_weight == 0) {
+            return Err(ConfigError::Invalid {
+                key: "spawn_weight".into(),
+                reason: "must be greater than 0 for at least one type".into(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl RangedDef {
+    fn validate(&self) -> Result<(), ConfigError> {
+        non_negative("preferred_distance", self.preferred_distance)?;
+        non_negative("strafe_speed", self.strafe_speed)?;
+        positive("fire_range", self.fire_range)?;
+        positive("fire_interval_secs", self.fire_interval_secs)?;
+        non_negative("telegraph_secs", self.telegraph_secs)?;
+        positive("projectile_speed", self.projectile_speed)?;
+        non_negative("projectile_damage", self.projectile_damage)?;
+        positive("projectile_lifetime_secs", self.projectile_lifetime_secs)
+    }
+}
+
+impl ChargerDef {
+    fn validate(&self) -> Result<(), ConfigError> {
+        non_negative("trigger_range", self.trigger_range)?;
+        non_negative("windup_secs", self.windup_secs)?;
+        non_negative("dash_speed", self.dash_speed)?;
+        non_negative("dash_secs", self.dash_secs)?;
+        non_negative("cooldown_secs", self.cooldown_secs)
+    }
+}
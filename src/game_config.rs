@@ -0,0 +1,516 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use bevy::asset::io::{file::FileAssetReader, Reader};
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::utils::Duration;
+use serde::Deserialize;
+
+use crate::aim::AimMode;
+use crate::elite::EliteAffix;
+use crate::pickup::Reward;
+use crate::state::GameState;
+use crate::*;
+
+pub struct GameConfigPlugin;
+
+/// Gameplay tuning, read from `assets/game.config.ron` at startup and
+/// re-applied whenever the file changes on disk.
+/// Missing keys fall back to the defaults in `configs.rs`.
+/// Speeds are in world units per second.
+#[derive(Resource, Asset, TypePath, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    pub world: WorldConfig,
+    pub player: PlayerConfig,
+    pub enemy: EnemyConfig,
+    pub gun: GunConfig,
+    pub collision: CollisionConfig,
+    pub score: ScoreConfig,
+    pub aim: AimConfig,
+    pub elite: EliteConfig,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorldConfig {
+    pub num_decorations: usize,
+    pub num_trees: usize,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayerConfig {
+    pub speed: f32,
+    pub health: f32,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnemyConfig {
+    pub max_num_enemies: usize,
+    pub spawn_rate_per_second: usize,
+    pub spawn_interval: f32,
+    /// Multiply the stats every enemy type has in `assets/enemies.ron`.
+    pub health_scale: f32,
+    pub speed_scale: f32,
+    pub damage_scale: f32,
+    /// Most projectiles from ranged enemies in flight at once.
+    pub max_projectiles: usize,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct GunConfig {
+    /// Inventory slots at the start of every run, from `BUILT_IN_WEAPONS`.
+    /// The first one is equipped.
+    pub weapons: Vec<String>,
+    pub bullet_radius: f32,
+    /// Projectiles recycled instead of despawned. Shots past it spawn
+    /// throwaway entities.
+    pub projectile_pool_size: usize,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CollisionConfig {
+    pub kd_tree_refresh_rate: f32,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AimConfig {
+    pub mode: AimMode,
+    /// Enemies farther from the player are never targeted.
+    pub range: f32,
+    /// Full width of the cone around the cursor direction `Assist` looks in.
+    pub assist_cone_degrees: f32,
+    /// How far `Assist` turns toward the target, from 0 (not at all) to 1.
+    pub assist_strength: f32,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoreConfig {
+    /// A kill within this many seconds of the previous one extends the combo.
+    pub combo_window_secs: f32,
+    /// Combo kills needed to raise the multiplier by one.
+    pub combo_kills_per_step: u32,
+    pub max_combo_multiplier: u32,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct EliteConfig {
+    /// Chance of a spawned enemy being elite, from 0 to 1, going up by
+    /// `chance_per_minute` over the run until it reaches `max_chance`.
+    pub base_chance: f32,
+    pub chance_per_minute: f32,
+    pub max_chance: f32,
+    /// Different affixes rolled for each elite.
+    pub affixes: usize,
+    /// Taken off every hit on an `Armored` elite.
+    pub armor: f32,
+    /// Speed multiplier of `Hasted` elites.
+    pub haste: f32,
+    /// Share of its maximum health a `Regenerating` elite heals per second.
+    pub regeneration: f32,
+    /// Health a `Vampiric` elite heals per point of contact damage it deals.
+    pub life_steal: f32,
+    /// Damage to the player within `explosion_radius` when a `Volatile` elite dies.
+    pub explosion_radius: f32,
+    pub explosion_damage: f32,
+    /// Dropped by every elite when it dies.
+    pub rewards: Vec<Reward>,
+}
+
+#[derive(Resource)]
+struct GameConfigHandle(Handle<GameConfig>);
+
+/// Sent after a modified config file has been applied to the `GameConfig` resource.
+/// Systems that read the config every frame don't need it, only those keeping
+/// state built from it.
+#[derive(Event)]
+pub struct GameConfigReloaded;
+
+#[derive(Default)]
+struct GameConfigLoader;
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Invalid { key: String, reason: String },
+}
+
+impl Plugin for GameConfigPlugin {
+    fn build(&self, app: &mut App) {
+        let path = FileAssetReader::get_base_path()
+            .join("assets")
+            .join(GAME_CONFIG_PATH);
+        let config = match GameConfig::from_file(&path) {
+            Ok(config) => config,
+            Err(ConfigError::Io(err)) if err.kind() == std::io::ErrorKind::NotFound => {
+                warn!("{} not found, using default game config", path.display());
+                GameConfig::default()
+            }
+            Err(err) => panic!("Invalid game config {}: {err}", path.display()),
+        };
+
+        app.insert_resource(config)
+            .init_asset::<GameConfig>()
+            .register_asset_loader(GameConfigLoader)
+            .add_event::<GameConfigReloaded>()
+            .add_systems(Startup, watch_game_config)
+            .add_systems(PreUpdate, apply_game_config_changes);
+    }
+}
+
+fn watch_game_config(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(GameConfigHandle(asset_server.load(GAME_CONFIG_PATH)));
+}
+
+fn apply_game_config_changes(
+    mut config: ResMut<GameConfig>,
+    handle: Res<GameConfigHandle>,
+    assets: Res<Assets<GameConfig>>,
+    mut asset_events: EventReader<AssetEvent<GameConfig>>,
+    mut ew: EventWriter<GameConfigReloaded>,
+) {
+    for event in asset_events.read() {
+        // The initial load matches what `GameConfigPlugin` already read from disk,
+        // so only file modifications are applied.
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        if *id != handle.0.id() {
+            continue;
+        }
+
+        if let Some(new_config) = assets.get(*id) {
+            if *config != *new_config {
+                *config = new_config.clone();
+                info!("Game config reloaded");
+                ew.send(GameConfigReloaded);
+            }
+        }
+    }
+}
+
+impl AssetLoader for GameConfigLoader {
+    type Asset = GameConfig;
+    type Settings = ();
+    type Error = ConfigError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<GameConfig, ConfigError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(ConfigError::Io)?;
+        GameConfig::from_bytes(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["config.ron"]
+    }
+}
+
+impl GameConfig {
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let bytes = fs::read(path).map_err(ConfigError::Io)?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ConfigError> {
+        let mut deserializer = ron::Deserializer::from_bytes(bytes).map_err(ConfigError::Parse)?;
+        let config: Self = serde_path_to_error::deserialize(&mut deserializer)
+            .map_err(|err| unknown_key_error(&deserializer, err))?;
+        deserializer
+            .end()
+            .map_err(|err| ConfigError::Parse(deserializer.span_error(err)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        positive("world.width", self.world.width)?;
+        positive("world.height", self.world.height)?;
+        positive("player.speed", self.player.speed)?;
+        positive("player.health", self.player.health)?;
+        positive("enemy.spawn_interval", self.enemy.spawn_interval)?;
+        positive("enemy.health_scale", self.enemy.health_scale)?;
+        non_negative("enemy.speed_scale", self.enemy.speed_scale)?;
+        non_negative("enemy.damage_scale", self.enemy.damage_scale)?;
+        if self.gun.weapons.is_empty() || self.gun.weapons.len() > MAX_WEAPON_SLOTS {
+            return Err(ConfigError::Invalid {
+                key: "gun.weapons".into(),
+                reason: format!("must list between 1 and {MAX_WEAPON_SLOTS} weapons"),
+            });
+        }
+        if !self
+            .gun
+            .weapons
+            .iter()
+            .all(|weapon| BUILT_IN_WEAPONS.contains(&weapon.as_str()))
+        {
+            return Err(ConfigError::Invalid {
+                key: "gun.weapons".into(),
+                reason: "must only contain built-in weapons".into(),
+            });
+        }
+        non_negative("gun.bullet_radius", self.gun.bullet_radius)?;
+        if self.gun.projectile_pool_size > MAX_PROJECTILE_POOL_SIZE {
+            return Err(ConfigError::Invalid {
+                key: "gun.projectile_pool_size".into(),
+                reason: format!("must be at most {MAX_PROJECTILE_POOL_SIZE}"),
+            });
+        }
+        positive(
+            "collision.kd_tree_refresh_rate",
+            self.collision.kd_tree_refresh_rate,
+        )?;
+        positive("score.combo_window_secs", self.score.combo_window_secs)?;
+        if self.score.combo_kills_per_step == 0 {
+            return Err(ConfigError::Invalid {
+                key: "score.combo_kills_per_step".into(),
+                reason: "must be greater than 0".into(),
+            });
+        }
+        if self.score.max_combo_multiplier == 0 {
+            return Err(ConfigError::Invalid {
+                key: "score.max_combo_multiplier".into(),
+                reason: "must be greater than 0".into(),
+            });
+        }
+        positive("aim.range", self.aim.range)?;
+        if !(self.aim.assist_cone_degrees > 0.0 && self.aim.assist_cone_degrees <= 360.0) {
+            return Err(ConfigError::Invalid {
+                key: "aim.assist_cone_degrees".into(),
+                reason: "must be greater than 0 and at most 360".into(),
+            });
+        }
+        if !(0.0..=1.0).contains(&self.aim.assist_strength) {
+            return Err(ConfigError::Invalid {
+                key: "aim.assist_strength".into(),
+                reason: "must be between 0 and 1".into(),
+            });
+        }
+        let elite = &self.elite;
+        for (key, chance) in [
+            ("elite.base_chance", elite.base_chance),
+            ("elite.max_chance", elite.max_chance),
+        ] {
+            if !(0.0..=1.0).contains(&chance) {
+                return Err(ConfigError::Invalid {
+                    key: key.into(),
+                    reason: "must be between 0 and 1".into(),
+                });
+            }
+        }
+        if elite.base_chance > elite.max_chance {
+            return Err(ConfigError::Invalid {
+                key: "elite.base_chance".into(),
+                reason: "must not be greater than `elite.max_chance`".into(),
+            });
+        }
+        non_negative("elite.chance_per_minute", elite.chance_per_minute)?;
+        if elite.affixes == 0 || elite.affixes > EliteAffix::ALL.len() {
+            return Err(ConfigError::Invalid {
+                key: "elite.affixes".into(),
+                reason: format!("must be between 1 and {}", EliteAffix::ALL.len()),
+            });
+        }
+        non_negative("elite.armor", elite.armor)?;
+        positive("elite.haste", elite.haste)?;
+        non_negative("elite.regeneration", elite.regeneration)?;
+        non_negative("elite.life_steal", elite.life_steal)?;
+        non_negative("elite.explosion_radius", elite.explosion_radius)?;
+        non_negative("elite.explosion_damage", elite.explosion_damage)?;
+        Ok(())
+    }
+}
+
+/// Names an unknown field by its full key, like validation errors do.
+fn unknown_key_error(
+    deserializer: &ron::Deserializer,
+    err: serde_path_to_error::Error<ron::Error>,
+) -> ConfigError {
+    let key = err.path().to_string();
+    let code = err.into_inner();
+    if !matches!(code, ron::Error::NoSuchStructField { .. }) {
+        return ConfigError::Parse(deserializer.span_error(code));
+    }
+    ConfigError::Invalid {
+        key,
+        reason: "is not a known setting".into(),
+    }
+}
+
+pub(crate) fn positive(key: &'static str, value: f32) -> Result<(), ConfigError> {
+    if value.is_finite() && value > 0.0 {
+        return Ok(());
+    }
+
+    Err(ConfigError::Invalid {
+        key: key.into(),
+        reason: "must be a finite number greater than 0".into(),
+    })
+}
+
+pub(crate) fn non_negative(key: &'static str, value: f32) -> Result<(), ConfigError> {
+    if value.is_finite() && value >= 0.0 {
+        return Ok(());
+    }
+
+    Err(ConfigError::Invalid {
+        key: key.into(),
+        reason: "must be a finite number greater than or equal to 0".into(),
+    })
+}
+
+/// Like `on_timer`, but reads the interval from `GameConfig` on every tick.
+/// The timer only advances during `GameState::InGame` and restarts with every
+/// run, so runs with the same input tick it identically.
+#[allow(clippy::type_complexity)]
+pub fn on_config_timer(
+    interval: fn(&GameConfig) -> f32,
+) -> impl FnMut(Local<Timer>, Res<Time>, Res<GameConfig>, Res<State<GameState>>) -> bool + Clone {
+    move |mut timer: Local<Timer>,
+          time: Res<Time>,
+          config: Res<GameConfig>,
+          state: Res<State<GameState>>| {
+        match state.get() {
+            GameState::InGame => {}
+            GameState::GameInit => {
+                timer.reset();
+                return false;
+            }
+            _ => return false,
+        }
+
+        let duration = Duration::from_secs_f32(interval(&config));
+        if timer.duration() != duration || timer.mode() != TimerMode::Repeating {
+            timer.set_duration(duration);
+            timer.set_mode(TimerMode::Repeating);
+        }
+
+        timer.tick(time.delta());
+        timer.just_finished()
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "{err}"),
+            ConfigError::Parse(err) => write!(f, "{err}"),
+            ConfigError::Invalid { key, reason } => write!(f, "`{key}` {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        Self {
+            num_decorations: NUM_WORLD_DECORATIONS,
+            num_trees: NUM_WORLD_TREES,
+            width: WORLD_W,
+            height: WORLD_H,
+        }
+    }
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        Self {
+            speed: PLAYER_SPEED,
+            health: PLAYER_HEALTH,
+        }
+    }
+}
+
+impl Default for EnemyConfig {
+    fn default() -> Self {
+        Self {
+            max_num_enemies: MAX_NUM_ENEMIES,
+            spawn_rate_per_second: SPAWN_RATE_PER_SECOND,
+            spawn_interval: ENEMY_SPAWN_INTERVAL,
+            health_scale: ENEMY_HEALTH_SCALE,
+            speed_scale: ENEMY_SPEED_SCALE,
+            damage_scale: ENEMY_DAMAGE_SCALE,
+            max_projectiles: MAX_ENEMY_PROJECTILES,
+        }
+    }
+}
+
+impl Default for GunConfig {
+    fn default() -> Self {
+        Self {
+            weapons: STARTING_WEAPONS.map(String::from).to_vec(),
+            bullet_radius: BULLET_RADIUS,
+            projectile_pool_size: PROJECTILE_POOL_SIZE,
+        }
+    }
+}
+
+impl Default for CollisionConfig {
+    fn default() -> Self {
+        Self {
+            kd_tree_refresh_rate: KD_TREE_REFRESH_RATE,
+        }
+    }
+}
+
+impl Default for AimConfig {
+    fn default() -> Self {
+        Self {
+            mode: AimMode::default(),
+            range: AIM_RANGE,
+            assist_cone_degrees: AIM_ASSIST_CONE_DEGREES,
+            assist_strength: AIM_ASSIST_STRENGTH,
+        }
+    }
+}
+
+impl Default for EliteConfig {
+    fn default() -> Self {
+        Self {
+            base_chance: ELITE_BASE_CHANCE,
+            chance_per_minute: ELITE_CHANCE_PER_MINUTE,
+            max_chance: ELITE_MAX_CHANCE,
+            affixes: ELITE_AFFIXES,
+            armor: ELITE_ARMOR,
+            haste: ELITE_HASTE,
+            regeneration: ELITE_REGENERATION,
+            life_steal: ELITE_LIFE_STEAL,
+            explosion_radius: ELITE_EXPLOSION_RADIUS,
+            explosion_damage: ELITE_EXPLOSION_DAMAGE,
+            rewards: vec![
+                Reward::Health(ELITE_HEALTH_REWARD),
+                Reward::Score(ELITE_SCORE_REWARD),
+            ],
+        }
+    }
+}
+
+impl Default for ScoreConfig {
+    fn default() -> Self {
+        Self {
+            combo_window_secs: COMBO_WINDOW_SECS,
+            combo_kills_per_step: COMBO_KILLS_PER_STEP,
+            max_combo_multiplier: MAX_COMBO_MULTIPLIER,
+        }
+    }
+}
@@ -1,17 +1,52 @@
 use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
 use bevy::prelude::*;
 
-use crate::enemy::Enemy;
+use crate::boss::Boss;
+use crate::boss_def::BossDefs;
+use crate::enemy::{Enemy, EnemyType};
+use crate::game_config::GameConfig;
+use crate::gun::{Gun, WeaponInventory};
+use crate::high_score::{GameMode, HighScores};
 use crate::player::{Health, Player};
 use crate::state::GameState;
+use crate::stats::RunStats;
+use crate::weapon::WeaponDef;
 use crate::world::GameEntity;
+use crate::*;
 
 pub struct GuiPlugin;
 
 #[derive(Component)]
 struct DebugText;
 #[derive(Component)]
+struct ScoreText;
+#[derive(Component)]
+struct WeaponText;
+#[derive(Component)]
+struct BossBar;
+#[derive(Component)]
+struct BossBarFill;
+#[derive(Component)]
+struct BossNameText;
+#[derive(Component)]
 struct MainMenuItem;
+#[derive(Component)]
+struct PauseMenuItem;
+
+#[derive(Component)]
+enum PauseMenuButton {
+    Resume,
+    Restart,
+    Quit,
+}
+#[derive(Component)]
+struct GameOverItem;
+
+#[derive(Component)]
+enum GameOverButton {
+    Retry,
+    MainMenu,
+}
 
 impl Plugin for GuiPlugin {
     fn build(&self, app: &mut App) {
@@ -22,10 +57,36 @@ impl Plugin for GuiPlugin {
                 Update,
                 handle_main_menu_buttons.run_if(in_state(GameState::MainMenu)),
             )
-            .add_systems(OnEnter(GameState::GameInit), spawn_debug_text)
+            .add_systems(OnEnter(GameState::Paused), setup_pause_menu)
+            .add_systems(OnExit(GameState::Paused), despawn_pause_menu)
+            .add_systems(
+                Update,
+                handle_pause_menu_buttons.run_if(in_state(GameState::Paused)),
+            )
+            .add_systems(OnEnter(GameState::GameOver), setup_game_over_screen)
+            .add_systems(OnExit(GameState::GameOver), despawn_game_over_screen)
+            .add_systems(
+                Update,
+                handle_game_over_buttons.run_if(in_state(GameState::GameOver)),
+            )
+            .add_systems(
+                OnEnter(GameState::GameInit),
+                (
+                    spawn_debug_text,
+                    spawn_score_text,
+                    spawn_weapon_text,
+                    spawn_boss_bar,
+                ),
+            )
             .add_systems(
                 Update,
-                update_debug_text.run_if(in_state(GameState::InGame)),
+                (
+                    update_debug_text,
+                    update_score_text,
+                    update_weapon_text,
+                    update_boss_bar,
+                )
+                    .run_if(in_state(GameState::InGame)),
             );
     }
 }
@@ -70,7 +131,6 @@ fn spawn_debug_text(mut commands: Commands, asset_server: Res<AssetServer>) {
                                 font: asset_server.load("monogram.ttf"),
                                 font_size: 40.0,
                                 color: Color::WHITE,
-                                ..default()
                             },
                         ),
                         DebugText,
@@ -100,7 +160,193 @@ fn update_debug_text(
     }
 }
 
-fn setup_main_menu(mut commands: Commands) {
+fn spawn_score_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "Score: 0",
+            TextStyle {
+                font: asset_server.load("monogram.ttf"),
+                font_size: 40.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_text_justify(JustifyText::Right)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(20.0),
+            ..default()
+        }),
+        ScoreText,
+        GameEntity,
+    ));
+}
+
+fn update_score_text(
+    config: Res<GameConfig>,
+    stats: Res<RunStats>,
+    mut query: Query<&mut Text, With<ScoreText>>,
+) {
+    if query.is_empty() {
+        return;
+    }
+
+    let mut text = query.single_mut();
+    text.sections[0].value = if stats.combo > 1 {
+        format!(
+            "Score: {}\nCombo: {} (x{})",
+            stats.score,
+            stats.combo,
+            stats.combo_multiplier(&config)
+        )
+    } else {
+        format!("Score: {}", stats.score)
+    };
+}
+
+fn spawn_weapon_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("monogram.ttf"),
+                font_size: 40.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_text_justify(JustifyText::Right)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            right: Val::Px(20.0),
+            ..default()
+        }),
+        WeaponText,
+        GameEntity,
+    ));
+}
+
+fn update_weapon_text(
+    weapon_defs: Res<Assets<WeaponDef>>,
+    player_query: Query<&WeaponInventory, With<Player>>,
+    gun_query: Query<&Gun>,
+    mut query: Query<&mut Text, With<WeaponText>>,
+) {
+    if query.is_empty() || player_query.is_empty() || gun_query.is_empty() {
+        return;
+    }
+
+    let inventory = player_query.single();
+    let slot = inventory.active_slot();
+    let Some(weapon) = weapon_defs.get(&slot.weapon) else {
+        return;
+    };
+
+    let reserve = match slot.reserve {
+        Some(reserve) => reserve.to_string(),
+        None => "inf".to_string(),
+    };
+    let ammo = match &gun_query.single().reload {
+        Some(reload) => format!("Reloading {:.0}%", reload.fraction() * 100.0),
+        None if slot.magazine == 0 && slot.reserve == Some(0) => "Out of ammo".to_string(),
+        None => format!("{} / {reserve}", slot.magazine),
+    };
+
+    let mut text = query.single_mut();
+    text.sections[0].value = format!("[{}] {}\n{ammo}", inventory.active + 1, weapon.name);
+}
+
+/// Hidden until a boss shows up.
+fn spawn_boss_bar(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    left: Val::Percent(30.0),
+                    width: Val::Percent(40.0),
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            BossBar,
+            GameEntity,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("monogram.ttf"),
+                        font_size: 40.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                BossNameText,
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Px(20.0),
+                        border: UiRect::all(Val::Px(3.0)),
+                        ..default()
+                    },
+                    background_color: BackgroundColor::from(Color::BLACK.with_alpha(0.9)),
+                    border_color: BorderColor(Color::WHITE),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    let (r, g, b) = BOSS_BAR_COLOR;
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: BackgroundColor::from(Color::srgb(r, g, b)),
+                            ..default()
+                        },
+                        BossBarFill,
+                    ));
+                });
+        });
+}
+
+fn update_boss_bar(
+    boss_defs: Res<BossDefs>,
+    boss_query: Query<(&Enemy, &Boss)>,
+    mut bar_query: Query<&mut Visibility, With<BossBar>>,
+    mut fill_query: Query<&mut Style, With<BossBarFill>>,
+    mut text_query: Query<&mut Text, With<BossNameText>>,
+) {
+    if bar_query.is_empty() || fill_query.is_empty() || text_query.is_empty() {
+        return;
+    }
+
+    let mut visibility = bar_query.single_mut();
+    let Some((enemy, boss)) = boss_query.iter().next() else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    *visibility = Visibility::Inherited;
+
+    let health_fraction = (enemy.health / boss.max_health).clamp(0.0, 1.0);
+    fill_query.single_mut().width = Val::Percent(health_fraction * 100.0);
+    if let Some(def) = boss_defs.0.get(boss.def) {
+        text_query.single_mut().sections[0]
+            .value
+            .clone_from(&def.name);
+    }
+}
+
+fn setup_main_menu(mut commands: Commands, high_scores: Option<Res<HighScores>>) {
     commands
         .spawn(NodeBundle {
             style: Style {
@@ -108,6 +354,8 @@ fn setup_main_menu(mut commands: Commands) {
                 height: Val::Percent(100.0),
                 align_items: AlignItems::Center,
                 justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(30.0),
                 ..default()
             },
             ..default()
@@ -136,20 +384,54 @@ fn setup_main_menu(mut commands: Commands) {
                         },
                     ));
                 });
+
+            if let Some(high_scores) = high_scores {
+                parent.spawn(TextBundle::from_section(
+                    high_score_table(&high_scores),
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ));
+            }
         })
         .insert(MainMenuItem);
 }
 
+fn high_score_table(high_scores: &HighScores) -> String {
+    if high_scores.entries().is_empty() {
+        return "No high scores yet".to_string();
+    }
+
+    let mut table = "High scores\n".to_string();
+    for (rank, entry) in high_scores.entries().iter().enumerate() {
+        let secs = entry.survival_secs as u64;
+        let mode = match entry.mode {
+            GameMode::Random => "random",
+            GameMode::Seeded => "seeded",
+        };
+        table += &format!(
+            "\n{:>2}. {:>8}  {}:{:02}  {:>5} kills  seed {} ({mode})  {}",
+            rank + 1,
+            entry.score,
+            secs / 60,
+            secs % 60,
+            entry.kills,
+            entry.seed,
+            entry.date()
+        );
+    }
+    table
+}
+
 fn handle_main_menu_buttons(
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
     for interaction in interaction_query.iter() {
-        match interaction {
-            Interaction::Pressed => {
-                next_state.set(GameState::GameInit);
-            }
-            _ => {}
+        if *interaction == Interaction::Pressed {
+            next_state.set(GameState::GameInit);
         }
     }
 }
@@ -159,3 +441,207 @@ fn despawn_main_menu(mut commands: Commands, menu_items_query: Query<Entity, Wit
         commands.entity(e).despawn_recursive();
     }
 }
+
+fn setup_pause_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(20.0),
+                    ..default()
+                },
+                background_color: BackgroundColor::from(Color::BLACK.with_alpha(0.6)),
+                ..default()
+            },
+            PauseMenuItem,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Paused",
+                TextStyle {
+                    font_size: 60.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+
+            for (label, button) in [
+                ("Resume", PauseMenuButton::Resume),
+                ("Restart", PauseMenuButton::Restart),
+                ("Quit to menu", PauseMenuButton::Quit),
+            ] {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(250.0),
+                                height: Val::Px(65.0),
+                                border: UiRect::all(Val::Px(5.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            border_color: BorderColor(Color::BLACK),
+                            ..default()
+                        },
+                        button,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            label,
+                            TextStyle {
+                                font_size: 40.0,
+                                color: Color::BLACK,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+fn handle_pause_menu_buttons(
+    interaction_query: Query<(&Interaction, &PauseMenuButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        next_state.set(match button {
+            PauseMenuButton::Resume => GameState::InGame,
+            PauseMenuButton::Restart => GameState::GameInit,
+            PauseMenuButton::Quit => GameState::MainMenu,
+        });
+    }
+}
+
+fn despawn_pause_menu(
+    mut commands: Commands,
+    menu_items_query: Query<Entity, With<PauseMenuItem>>,
+) {
+    for e in menu_items_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+fn setup_game_over_screen(mut commands: Commands, stats: Res<RunStats>) {
+    let secs = stats.survival_time.as_secs();
+    let mut summary = format!(
+        "Score: {}\nSurvived: {}:{:02}\nKills: {}\n",
+        stats.score,
+        secs / 60,
+        secs % 60,
+        stats.total_kills()
+    );
+    for enemy_type in EnemyType::ALL {
+        let kills = stats.kills.get(&enemy_type).copied().unwrap_or_default();
+        summary += &format!("  {enemy_type:?}: {kills}\n");
+    }
+    summary += &format!("  Bosses: {}\n", stats.bosses_killed);
+    summary += &format!(
+        "Shots fired: {}\nAccuracy: {:.1}%\nDamage taken: {:.0}",
+        stats.shots_fired,
+        stats.accuracy() * 100.0,
+        stats.damage_taken
+    );
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(20.0),
+                    ..default()
+                },
+                background_color: BackgroundColor::from(Color::BLACK.with_alpha(0.8)),
+                ..default()
+            },
+            GameOverItem,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Game Over",
+                TextStyle {
+                    font_size: 60.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+            parent.spawn(TextBundle::from_section(
+                summary,
+                TextStyle {
+                    font_size: 30.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+
+            for (label, button) in [
+                ("Retry", GameOverButton::Retry),
+                ("Main menu", GameOverButton::MainMenu),
+            ] {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(250.0),
+                                height: Val::Px(65.0),
+                                border: UiRect::all(Val::Px(5.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            border_color: BorderColor(Color::BLACK),
+                            ..default()
+                        },
+                        button,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            label,
+                            TextStyle {
+                                font_size: 40.0,
+                                color: Color::BLACK,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+fn handle_game_over_buttons(
+    interaction_query: Query<(&Interaction, &GameOverButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        next_state.set(match button {
+            GameOverButton::Retry => GameState::GameInit,
+            GameOverButton::MainMenu => GameState::MainMenu,
+        });
+    }
+}
+
+fn despawn_game_over_screen(
+    mut commands: Commands,
+    menu_items_query: Query<Entity, With<GameOverItem>>,
+) {
+    for e in menu_items_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
@@ -1,4 +1,3 @@
-use bevy::utils::Instant;
 use std::f32::consts::PI;
 
 use bevy::math::{vec2, vec3};
@@ -6,51 +5,93 @@ use bevy::prelude::*;
 use bevy::time::Stopwatch;
 use rand::Rng;
 
+use crate::aim::{AimPosition, AimSet};
+use crate::input::{PlayerInput, WeaponSwitch};
 use crate::player::Player;
+use crate::projectile::{release_projectile, ProjectilePool, Ricochet};
+use crate::rng::{GameRng, RngStream};
 use crate::state::GameState;
+use crate::stats::RunStats;
+use crate::weapon::WeaponDef;
+use crate::world::GameEntity;
 use crate::*;
 
 pub struct GunPlugin;
 
 #[derive(Component)]
-pub struct Gun;
+pub struct Gun {
+    pub weapon: Handle<WeaponDef>,
+    /// Running while the gun reloads, which blocks firing.
+    pub reload: Option<Timer>,
+}
 #[derive(Component)]
 pub struct GunTimer(pub Stopwatch);
+/// Weapons carried by the player, one per slot.
+#[derive(Component)]
+pub struct WeaponInventory {
+    pub slots: Vec<WeaponSlot>,
+    pub active: usize,
+}
+/// A carried weapon and its ammo, kept while other weapons are in use.
+pub struct WeaponSlot {
+    pub weapon: Handle<WeaponDef>,
+    pub magazine: u32,
+    /// `None` for unlimited reserve ammo.
+    pub reserve: Option<u32>,
+}
 #[derive(Component)]
-pub struct Bullet;
+pub struct Bullet {
+    pub damage: f32,
+}
+/// Enemies the bullet can still pass through, and the ones it already hit.
+#[derive(Component, Default)]
+pub struct Pierce {
+    pub remaining: u32,
+    pub hit: Vec<Entity>,
+}
+/// Counts down in game time, so bullets don't age while paused and follow
+/// slow motion or fast-forward.
 #[derive(Component)]
-pub struct SpawnInstant(Instant);
+struct Lifetime(Timer);
+/// World units per second.
 #[derive(Component)]
-struct BulletDirection(Vec3);
+pub struct BulletVelocity(pub Vec3);
 
 impl Plugin for GunPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
             (
-                update_gun_transform,
+                (
+                    update_gun_transform,
+                    switch_weapon,
+                    reload_gun,
+                    handle_gun_input,
+                )
+                    .chain()
+                    .after(AimSet),
                 update_bullets,
-                handle_gun_input,
-                despawn_old_bullets,
+                expire_bullets,
             )
                 .run_if(in_state(GameState::InGame)),
         );
     }
 }
 
-fn despawn_old_bullets(
+fn expire_bullets(
     mut commands: Commands,
-    bullet_query: Query<(&SpawnInstant, Entity), With<Bullet>>,
+    time: Res<Time>,
+    mut bullet_query: Query<(&mut Lifetime, Entity), With<Bullet>>,
 ) {
-    for (instant, e) in bullet_query.iter() {
-        if instant.0.elapsed().as_secs_f32() > BULLET_TIME_SECS {
-            commands.entity(e).despawn();
+    for (mut lifetime, e) in bullet_query.iter_mut() {
+        if lifetime.0.tick(time.delta()).finished() {
+            release_projectile(&mut commands, e);
         }
     }
 }
 
 fn update_gun_transform(
-    cursor_pos: Res<CursorPosition>,
+    aim_pos: Res<AimPosition>,
     player_query: Query<&Transform, With<Player>>,
     mut gun_query: Query<&mut Transform, (With<Gun>, Without<Player>)>,
 ) {
@@ -59,13 +100,10 @@ fn update_gun_transform(
     }
 
     let player_pos = player_query.single().translation.truncate();
-    let cursor_pos = match cursor_pos.0 {
-        Some(pos) => pos,
-        None => player_pos,
-    };
+    let aim_pos = aim_pos.0.unwrap_or(player_pos);
     let mut gun_transform = gun_query.single_mut();
 
-    let angle = (player_pos.y - cursor_pos.y).atan2(player_pos.x - cursor_pos.x) + PI;
+    let angle = (player_pos.y - aim_pos.y).atan2(player_pos.x - aim_pos.x) + PI;
     gun_transform.rotation = Quat::from_rotation_z(angle);
 
     let offset = 20.0;
@@ -78,37 +116,122 @@ fn update_gun_transform(
     gun_transform.translation.z = 15.0;
 }
 
+fn switch_weapon(
+    input: Res<PlayerInput>,
+    weapon_defs: Res<Assets<WeaponDef>>,
+    mut player_query: Query<&mut WeaponInventory, With<Player>>,
+    mut gun_query: Query<(&mut Gun, &mut TextureAtlas)>,
+) {
+    let Some(switch) = input.switch_weapon else {
+        return;
+    };
+    if player_query.is_empty() || gun_query.is_empty() {
+        return;
+    }
+
+    let mut inventory = player_query.single_mut();
+    if !inventory.select(switch) {
+        return;
+    }
+
+    // Switching away cancels a reload in progress.
+    let (mut gun, mut atlas) = gun_query.single_mut();
+    gun.weapon = inventory.active_slot().weapon.clone();
+    gun.reload = None;
+    if let Some(weapon) = weapon_defs.get(&gun.weapon) {
+        atlas.index = weapon.gun_sprite_index;
+    }
+}
+
+/// Reloads on `R` or when the magazine is empty, refilling it from the
+/// reserve once `reload_secs` have passed.
+fn reload_gun(
+    time: Res<Time>,
+    input: Res<PlayerInput>,
+    weapon_defs: Res<Assets<WeaponDef>>,
+    mut player_query: Query<&mut WeaponInventory, With<Player>>,
+    mut gun_query: Query<&mut Gun>,
+) {
+    if player_query.is_empty() || gun_query.is_empty() {
+        return;
+    }
+
+    let mut inventory = player_query.single_mut();
+    let mut gun = gun_query.single_mut();
+    let Some(weapon) = weapon_defs.get(&gun.weapon) else {
+        return;
+    };
+    let slot = inventory.active_slot_mut();
+
+    let Some(reload) = gun.reload.as_mut() else {
+        let wants_reload = input.reload || slot.magazine == 0;
+        if wants_reload && slot.can_reload(weapon) {
+            gun.reload = Some(Timer::from_seconds(weapon.reload_secs, TimerMode::Once));
+        }
+        return;
+    };
+
+    if reload.tick(time.delta()).finished() {
+        let missing = weapon.magazine_size.saturating_sub(slot.magazine);
+        let loaded = slot.reserve.map_or(missing, |reserve| reserve.min(missing));
+        slot.magazine += loaded;
+        if let Some(reserve) = slot.reserve.as_mut() {
+            *reserve -= loaded;
+        }
+        gun.reload = None;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn handle_gun_input(
     mut commands: Commands,
     time: Res<Time>,
-    mut gun_query: Query<(&Transform, &mut GunTimer), With<Gun>>,
-    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    weapon_defs: Res<Assets<WeaponDef>>,
+    mut rng: ResMut<GameRng>,
+    mut stats: ResMut<RunStats>,
+    mut player_query: Query<&mut WeaponInventory, With<Player>>,
+    mut gun_query: Query<(&Gun, &Transform, &mut GunTimer)>,
+    input: Res<PlayerInput>,
     handle: Res<GlobalTextureAtlas>,
+    mut pool: ResMut<ProjectilePool>,
 ) {
-    if gun_query.is_empty() {
+    if gun_query.is_empty() || player_query.is_empty() {
         return;
     }
 
-    let (gun_transform, mut gun_timer) = gun_query.single_mut();
+    let mut inventory = player_query.single_mut();
+    let (gun, gun_transform, mut gun_timer) = gun_query.single_mut();
     let gun_pos = gun_transform.translation.truncate();
     gun_timer.0.tick(time.delta());
 
-    if !mouse_button_input.pressed(MouseButton::Left) {
+    let Some(weapon) = weapon_defs.get(&gun.weapon) else {
+        return;
+    };
+    let slot = inventory.active_slot_mut();
+    if !input.fire
+        || gun.reload.is_some()
+        || slot.magazine == 0
+        || gun_timer.0.elapsed_secs() < weapon.fire_interval_secs()
+    {
         return;
     }
+    gun_timer.0.reset();
+    slot.magazine -= 1;
+    stats.shots_fired += weapon.projectiles_per_shot as u32;
 
-    let mut rng = rand::thread_rng();
-    let bullet_direction = gun_transform.local_x();
-    if gun_timer.0.elapsed_secs() >= BULLET_SPAWN_INTERVAL {
-        gun_timer.0.reset();
-
-        for _ in 0..NUM_BULLETS_PER_SHOT {
-            let dir = vec3(
-                bullet_direction.x + rng.gen_range(-0.5..0.5),
-                bullet_direction.y + rng.gen_range(-0.5..0.5),
-                bullet_direction.z,
-            );
-            commands.spawn((
+    let rng = rng.stream(RngStream::Gun);
+    let bullet_direction = gun_transform.local_x().truncate();
+    let half_spread = weapon.spread_degrees.to_radians() / 2.0;
+    for _ in 0..weapon.projectiles_per_shot {
+        let angle = if half_spread > 0.0 {
+            rng.gen_range(-half_spread..=half_spread)
+        } else {
+            0.0
+        };
+        let dir = Vec2::from_angle(angle).rotate(bullet_direction);
+        let mut bullet = pool.spawn(
+            &mut commands,
+            (
                 SpriteBundle {
                     texture: handle.image.clone().unwrap(),
                     transform: Transform::from_translation(vec3(gun_pos.x, gun_pos.y, 1.0))
@@ -117,23 +240,82 @@ fn handle_gun_input(
                 },
                 TextureAtlas {
                     layout: handle.layout.clone().unwrap(),
-                    index: 8,
+                    index: weapon.sprite_index,
                 },
-                Bullet,
-                BulletDirection(dir),
-                SpawnInstant(Instant::now()),
-            ));
-        }
+                Bullet {
+                    damage: weapon.damage,
+                },
+                BulletVelocity(dir.extend(0.0) * weapon.projectile_speed),
+                Lifetime(Timer::from_seconds(weapon.lifetime_secs, TimerMode::Once)),
+                GameEntity,
+            ),
+        );
+        weapon.modifiers.insert_into(&mut bullet);
     }
 }
 
-fn update_bullets(mut bullet_query: Query<(&mut Transform, &BulletDirection), With<Bullet>>) {
+pub fn update_bullets(
+    time: Res<Time>,
+    mut bullet_query: Query<(&mut Transform, &BulletVelocity, Option<&mut Ricochet>), With<Bullet>>,
+) {
     if bullet_query.is_empty() {
         return;
     }
 
-    for (mut t, dir) in bullet_query.iter_mut() {
-        t.translation += dir.0.normalize() * Vec3::splat(BULLET_SPEED);
+    for (mut t, velocity, ricochet) in bullet_query.iter_mut() {
+        if let Some(mut ricochet) = ricochet {
+            if ricochet.bounced {
+                ricochet.bounced = false;
+                continue;
+            }
+        }
+        t.translation += velocity.0 * time.delta_seconds();
         t.translation.z = 10.0;
     }
 }
+
+impl WeaponInventory {
+    pub fn active_slot(&self) -> &WeaponSlot {
+        &self.slots[self.active]
+    }
+
+    pub fn active_slot_mut(&mut self) -> &mut WeaponSlot {
+        &mut self.slots[self.active]
+    }
+
+    /// Returns whether the active slot changed. Next and previous wrap around,
+    /// empty slots are ignored.
+    pub fn select(&mut self, switch: WeaponSwitch) -> bool {
+        let num_slots = self.slots.len();
+        let active = match switch {
+            WeaponSwitch::Slot(slot) if (slot as usize) < num_slots => slot as usize,
+            WeaponSwitch::Slot(_) => return false,
+            WeaponSwitch::Next => (self.active + 1) % num_slots,
+            WeaponSwitch::Previous => (self.active + num_slots - 1) % num_slots,
+        };
+
+        let changed = active != self.active;
+        self.active = active;
+        changed
+    }
+}
+
+impl WeaponSlot {
+    /// Starts with a full magazine and the weapon's reserve.
+    pub fn new(weapon: Handle<WeaponDef>, def: &WeaponDef) -> Self {
+        Self {
+            weapon,
+            magazine: def.magazine_size,
+            reserve: def.reserve_ammo,
+        }
+    }
+
+    pub fn refill(&mut self, def: &WeaponDef) {
+        self.magazine = def.magazine_size;
+        self.reserve = def.reserve_ammo;
+    }
+
+    pub fn can_reload(&self, def: &WeaponDef) -> bool {
+        self.magazine < def.magazine_size && self.reserve != Some(0)
+    }
+}
@@ -0,0 +1,234 @@
+use std::path::PathBuf;
+
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy::utils::{Duration, Instant};
+use bevy_rapier2d::prelude::*;
+
+use crate::aim::AimPlugin;
+use crate::animation::AnimationPlugin;
+use crate::boss::BossPlugin;
+use crate::boss_def::BossDefPlugin;
+use crate::camera::FollowCameraPlugin;
+use crate::charger::ChargerPlugin;
+use crate::collision::CollisionPlugin;
+use crate::elite::ElitePlugin;
+use crate::enemy::EnemyPlugin;
+use crate::enemy_def::EnemyDefPlugin;
+use crate::game_config::{GameConfig, GameConfigPlugin};
+use crate::gun::GunPlugin;
+use crate::high_score::HighScorePlugin;
+use crate::input::PlayerInputPlugin;
+use crate::pause::PausePlugin;
+use crate::pickup::PickupPlugin;
+use crate::player::{Player, PlayerPlugin};
+use crate::projectile::ProjectilePlugin;
+use crate::ranged::RangedEnemyPlugin;
+use crate::replay::{Replay, ReplayMode, ReplayPlugin};
+use crate::rng::RngPlugin;
+use crate::state::GameState;
+use crate::stats::RunStatsPlugin;
+use crate::weapon::WeaponPlugin;
+use crate::world::WorldPlugin;
+use crate::*;
+
+/// Runs the game loop without a window or renderer, advancing `Time` by a
+/// fixed timestep (`HEADLESS_TIMESTEP_SECS` unless overridden) on every step.
+pub struct HeadlessApp {
+    app: App,
+    timestep: Duration,
+}
+
+#[derive(Default)]
+pub struct HeadlessAppBuilder {
+    config: Option<GameConfig>,
+    seed: Option<u64>,
+    replay_mode: Option<ReplayMode>,
+    high_scores_path: Option<PathBuf>,
+    timestep: Option<Duration>,
+}
+
+impl HeadlessAppBuilder {
+    /// Overrides the config read from `assets/game.config.ron`.
+    pub fn config(mut self, config: GameConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Changes the config passed to `config`, or the defaults.
+    pub fn configure(mut self, tweak: impl FnOnce(&mut GameConfig)) -> Self {
+        tweak(self.config.get_or_insert_with(GameConfig::default));
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Simulates a frame rate of `1.0 / secs`.
+    pub fn timestep(mut self, secs: f32) -> Self {
+        self.timestep = Some(Duration::from_secs_f32(secs));
+        self
+    }
+
+    /// Keeps a high score table in `path`.
+    pub fn high_scores(mut self, path: impl Into<PathBuf>) -> Self {
+        self.high_scores_path = Some(path.into());
+        self
+    }
+
+    /// Records every run into the `ReplayRecorder` resource.
+    pub fn record(mut self) -> Self {
+        self.replay_mode = Some(ReplayMode::Record { path: None });
+        self
+    }
+
+    /// Plays back `replay`, using its seed.
+    pub fn replay(mut self, replay: Replay) -> Self {
+        self.seed = Some(replay.seed);
+        self.replay_mode = Some(ReplayMode::Playback(replay));
+        self
+    }
+
+    pub fn build(self) -> HeadlessApp {
+        let timestep = self
+            .timestep
+            .unwrap_or(Duration::from_secs_f32(HEADLESS_TIMESTEP_SECS));
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            TransformPlugin,
+            HierarchyPlugin,
+            InputPlugin,
+            AssetPlugin {
+                watch_for_changes_override: Some(false),
+                ..default()
+            },
+            ImagePlugin::default(),
+        ))
+        .init_asset::<TextureAtlasLayout>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(timestep))
+        .add_plugins(GameConfigPlugin)
+        .add_plugins(RngPlugin { seed: self.seed })
+        .add_plugins(PlayerInputPlugin)
+        .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
+        .add_plugins(FollowCameraPlugin)
+        .add_plugins(WeaponPlugin)
+        .add_plugins(AimPlugin)
+        .add_plugins(GunPlugin)
+        .add_plugins(ProjectilePlugin)
+        .add_plugins(PlayerPlugin)
+        .add_plugins(AnimationPlugin)
+        .add_plugins(ResourcesPlugin)
+        .add_plugins(WorldPlugin)
+        .add_plugins(EnemyDefPlugin)
+        .add_plugins(EnemyPlugin)
+        .add_plugins(RangedEnemyPlugin)
+        .add_plugins(ChargerPlugin)
+        .add_plugins(ElitePlugin)
+        .add_plugins(BossDefPlugin)
+        .add_plugins(BossPlugin)
+        .add_plugins(PickupPlugin)
+        .add_plugins(CollisionPlugin)
+        .add_plugins(PausePlugin)
+        .add_plugins(RunStatsPlugin)
+        .add_systems(OnEnter(GameState::MainMenu), skip_main_menu);
+
+        if let Some(config) = self.config {
+            app.insert_resource(config);
+        }
+        if let Some(path) = self.high_scores_path {
+            app.add_plugins(HighScorePlugin { path: Some(path) });
+        }
+        if let Some(mode) = self.replay_mode {
+            app.add_plugins(ReplayPlugin { mode });
+        }
+
+        app.finish();
+        app.cleanup();
+
+        // Assets load in the background, so wait for them in real time.
+        let mut headless = HeadlessApp { app, timestep };
+        let started = Instant::now();
+        while headless.state() != GameState::InGame {
+            if started.elapsed() > Duration::from_secs(HEADLESS_STARTUP_TIMEOUT_SECS) {
+                panic!("Headless app did not reach GameState::InGame");
+            }
+            headless.step(1);
+        }
+
+        headless
+    }
+}
+
+impl HeadlessApp {
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    pub fn builder() -> HeadlessAppBuilder {
+        HeadlessAppBuilder::default()
+    }
+
+    pub fn step(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    pub fn step_secs(&mut self, secs: f32) {
+        self.step((secs / self.timestep.as_secs_f32()).round() as u32);
+    }
+
+    pub fn state(&self) -> GameState {
+        *self.app.world().resource::<State<GameState>>().get()
+    }
+
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    pub fn count<C: Component>(&mut self) -> usize {
+        self.app
+            .world_mut()
+            .query_filtered::<(), With<C>>()
+            .iter(self.app.world())
+            .count()
+    }
+
+    pub fn positions<C: Component>(&mut self) -> Vec<Vec2> {
+        self.app
+            .world_mut()
+            .query_filtered::<&Transform, With<C>>()
+            .iter(self.app.world())
+            .map(|transform| transform.translation.truncate())
+            .collect()
+    }
+
+    pub fn player_transform(&mut self) -> Option<Transform> {
+        self.app
+            .world_mut()
+            .query_filtered::<&Transform, With<Player>>()
+            .get_single(self.app.world())
+            .ok()
+            .copied()
+    }
+}
+
+impl Default for HeadlessApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn skip_main_menu(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::GameInit);
+}
@@ -0,0 +1,255 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::replay::is_replaying;
+use crate::rng::GameRng;
+use crate::state::{GameState, InRun};
+use crate::stats::RunStats;
+use crate::*;
+
+/// Keeps the best `NUM_HIGH_SCORES` runs in `path`, or only in memory when
+/// no path is set.
+pub struct HighScorePlugin {
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameMode {
+    /// Every run gets a fresh random seed.
+    Random,
+    /// Every run uses the seed passed with `--seed`, so runs are comparable.
+    Seeded,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    pub score: u64,
+    pub survival_secs: f32,
+    pub kills: u32,
+    pub seed: u64,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub mode: GameMode,
+}
+
+/// Best runs first.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct HighScores {
+    path: Option<PathBuf>,
+    entries: Vec<HighScoreEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HighScoreFile {
+    version: u32,
+    entries: Vec<HighScoreEntry>,
+}
+
+#[derive(Debug)]
+pub enum HighScoreError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+    UnsupportedVersion(u32),
+}
+
+const HIGH_SCORES_VERSION: u32 = 1;
+
+impl Default for HighScorePlugin {
+    fn default() -> Self {
+        Self {
+            path: default_high_scores_path(),
+        }
+    }
+}
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        let high_scores = match &self.path {
+            Some(path) => HighScores::load_or_default(path),
+            None => {
+                warn!("No data directory found, high scores won't be saved");
+                HighScores::default()
+            }
+        };
+
+        // `State<GameState>` already holds the next state during `OnExit`, so
+        // only runs that ended in a game over are recorded.
+        app.insert_resource(high_scores).add_systems(
+            OnExit(InRun),
+            record_high_score
+                .run_if(in_state(GameState::GameOver))
+                .run_if(not(is_replaying)),
+        );
+    }
+}
+
+/// `$XDG_DATA_HOME` or `~/.local/share` on Linux, `~/Library/Application Support`
+/// on macOS and `%APPDATA%` on Windows.
+fn default_high_scores_path() -> Option<PathBuf> {
+    let env_path = |key| std::env::var_os(key).filter(|value| !value.is_empty());
+    let data_dir = if cfg!(target_os = "windows") {
+        env_path("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env_path("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env_path("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env_path("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    }?;
+
+    Some(
+        data_dir
+            .join(HIGH_SCORES_DIR_NAME)
+            .join(HIGH_SCORES_FILE_NAME),
+    )
+}
+
+fn record_high_score(mut high_scores: ResMut<HighScores>, stats: Res<RunStats>, rng: Res<GameRng>) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_secs())
+        .unwrap_or_default();
+    let entry = HighScoreEntry {
+        score: stats.score,
+        survival_secs: stats.survival_time.as_secs_f32(),
+        kills: stats.total_kills(),
+        seed: rng.seed(),
+        timestamp,
+        mode: if rng.is_seed_fixed() {
+            GameMode::Seeded
+        } else {
+            GameMode::Random
+        },
+    };
+
+    let Some(rank) = high_scores.insert(entry) else {
+        return;
+    };
+    info!("New high score, rank {}", rank + 1);
+
+    if let Some(path) = high_scores.path.clone() {
+        if let Err(err) = high_scores.save(&path) {
+            error!("Failed to save high scores to {}: {err}", path.display());
+        }
+    }
+}
+
+impl HighScores {
+    /// Reads the table from `path`, starting a fresh one when the file is
+    /// missing. An unreadable file is moved aside to `<path>.bak` so it isn't
+    /// overwritten by the next save.
+    pub fn load_or_default(path: &Path) -> Self {
+        let entries = match Self::read_entries(path) {
+            Ok(entries) => entries,
+            Err(HighScoreError::Io(err)) if err.kind() == std::io::ErrorKind::NotFound => {
+                Vec::new()
+            }
+            Err(err) => {
+                let backup = path.with_extension("ron.bak");
+                warn!(
+                    "Ignoring high scores in {}: {err}. Moving it to {}",
+                    path.display(),
+                    backup.display()
+                );
+                if let Err(err) = fs::rename(path, &backup) {
+                    warn!("Failed to move {}: {err}", path.display());
+                }
+                Vec::new()
+            }
+        };
+
+        let mut high_scores = Self {
+            path: Some(path.to_path_buf()),
+            entries: Vec::new(),
+        };
+        for entry in entries {
+            high_scores.insert(entry);
+        }
+        high_scores
+    }
+
+    fn read_entries(path: &Path) -> Result<Vec<HighScoreEntry>, HighScoreError> {
+        let bytes = fs::read(path).map_err(HighScoreError::Io)?;
+        let file: HighScoreFile = ron::de::from_bytes(&bytes).map_err(HighScoreError::Parse)?;
+        if file.version != HIGH_SCORES_VERSION {
+            return Err(HighScoreError::UnsupportedVersion(file.version));
+        }
+
+        Ok(file.entries)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), HighScoreError> {
+        let file = HighScoreFile {
+            version: HIGH_SCORES_VERSION,
+            entries: self.entries.clone(),
+        };
+        let text = ron::ser::to_string_pretty(&file, ron::ser::PrettyConfig::default())
+            .map_err(HighScoreError::Serialize)?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(HighScoreError::Io)?;
+        }
+        fs::write(path, text).map_err(HighScoreError::Io)
+    }
+
+    pub fn entries(&self) -> &[HighScoreEntry] {
+        &self.entries
+    }
+
+    /// Adds `entry` if it makes the table and returns its rank, starting at 0.
+    /// Ties keep the older run first.
+    pub fn insert(&mut self, entry: HighScoreEntry) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .position(|other| other.score < entry.score)
+            .unwrap_or(self.entries.len());
+        if rank >= NUM_HIGH_SCORES {
+            return None;
+        }
+
+        self.entries.insert(rank, entry);
+        self.entries.truncate(NUM_HIGH_SCORES);
+        Some(rank)
+    }
+}
+
+impl HighScoreEntry {
+    /// UTC date of the run as `YYYY-MM-DD`.
+    pub fn date(&self) -> String {
+        // Civil-from-days conversion, see https://howardhinnant.github.io/date_algorithms.html
+        let days = (self.timestamp / 86400) as i64 + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+}
+
+impl fmt::Display for HighScoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HighScoreError::Io(err) => write!(f, "{err}"),
+            HighScoreError::Parse(err) => write!(f, "{err}"),
+            HighScoreError::Serialize(err) => write!(f, "{err}"),
+            HighScoreError::UnsupportedVersion(version) => {
+                write!(f, "unsupported high score file version {version}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HighScoreError {}
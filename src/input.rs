@@ -0,0 +1,210 @@
+use bevy::input::gamepad::GamepadButtonInput;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::mouse::{MouseButtonInput, MouseMotion, MouseWheel};
+use bevy::math::vec2;
+use bevy::prelude::*;
+
+use crate::replay::is_replaying;
+use crate::state::GameState;
+use crate::*;
+
+pub struct PlayerInputPlugin;
+
+/// Gameplay input for the current frame. Gameplay systems read this instead of
+/// `ButtonInput` so a replay can drive them with recorded input.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct PlayerInput {
+    pub up: bool,
+    pub down: bool,
+    pub left: bool,
+    pub right: bool,
+    /// Analog movement from a gamepad stick, used instead of the direction
+    /// flags when set. At most 1 long.
+    pub move_axis: Option<Vec2>,
+    /// Unit direction from a gamepad stick the gun points in, instead of the
+    /// cursor.
+    pub aim: Option<Vec2>,
+    pub fire: bool,
+    pub reload: bool,
+    pub switch_weapon: Option<WeaponSwitch>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeaponSwitch {
+    /// Inventory slot, starting at 0.
+    Slot(u8),
+    Next,
+    Previous,
+}
+
+/// Device the player used last. `PlayerInput` is read from it only, so an idle
+/// gamepad doesn't fight the mouse or the other way round.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ActiveInputDevice {
+    #[default]
+    KeyboardMouse,
+    Gamepad(Gamepad),
+}
+
+/// Held to zoom the camera with the mouse wheel instead of switching weapons.
+pub const ZOOM_MODIFIER_KEYS: [KeyCode; 2] = [KeyCode::ControlLeft, KeyCode::ControlRight];
+
+const SLOT_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// Systems that fill `PlayerInput` and `CursorPosition`; anything consuming
+/// them runs `.after(PlayerInputSet)`.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlayerInputSet;
+
+impl Plugin for PlayerInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayerInput>()
+            .init_resource::<ActiveInputDevice>()
+            .add_systems(
+                Update,
+                (update_active_device, read_player_input)
+                    .chain()
+                    .in_set(PlayerInputSet)
+                    .run_if(in_state(GameState::InGame))
+                    .run_if(not(is_replaying)),
+            );
+    }
+}
+
+/// Switches to whichever device was used this frame: a key, mouse button,
+/// mouse move or scroll, or a gamepad button or stick push.
+#[allow(clippy::too_many_arguments)]
+fn update_active_device(
+    mut active_device: ResMut<ActiveInputDevice>,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut gamepad_button_events: EventReader<GamepadButtonInput>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut mouse_button_events: EventReader<MouseButtonInput>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+) {
+    if let ActiveInputDevice::Gamepad(gamepad) = *active_device {
+        if !gamepads.contains(gamepad) {
+            *active_device = ActiveInputDevice::KeyboardMouse;
+        }
+    }
+
+    let used_gamepad = gamepad_button_events
+        .read()
+        .map(|event| event.button.gamepad)
+        .chain(gamepads.iter().filter(|&gamepad| {
+            let [left, right] = sticks(&axes, gamepad);
+            left.length().max(right.length()) >= GAMEPAD_ACTIVATION_THRESHOLD
+        }))
+        .last();
+    let used_keyboard_mouse = keyboard_events.read().count() > 0
+        || mouse_button_events.read().count() > 0
+        || mouse_motion_events.read().count() > 0
+        || mouse_wheel_events.read().count() > 0;
+
+    if used_keyboard_mouse {
+        *active_device = ActiveInputDevice::KeyboardMouse;
+    } else if let Some(gamepad) = used_gamepad {
+        *active_device = ActiveInputDevice::Gamepad(gamepad);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn read_player_input(
+    mut input: ResMut<PlayerInput>,
+    active_device: Res<ActiveInputDevice>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    gamepad_button_input: Res<ButtonInput<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+) {
+    if let ActiveInputDevice::Gamepad(gamepad) = *active_device {
+        mouse_wheel_events.clear();
+        let button = |button_type| GamepadButton::new(gamepad, button_type);
+        let [left_stick, right_stick] = sticks(&axes, gamepad);
+        let switch_weapon =
+            if gamepad_button_input.just_pressed(button(GamepadButtonType::RightTrigger)) {
+                Some(WeaponSwitch::Next)
+            } else if gamepad_button_input.just_pressed(button(GamepadButtonType::LeftTrigger)) {
+                Some(WeaponSwitch::Previous)
+            } else {
+                None
+            };
+
+        // The gun keeps pointing the same way once the stick is let go.
+        let aim = if right_stick.length() >= GAMEPAD_AIM_DEADZONE {
+            Some(right_stick.normalize())
+        } else {
+            input.aim
+        };
+        *input = PlayerInput {
+            move_axis: Some(if left_stick.length() >= GAMEPAD_MOVE_DEADZONE {
+                left_stick.clamp_length_max(1.0)
+            } else {
+                Vec2::ZERO
+            }),
+            aim,
+            fire: gamepad_button_input.pressed(button(GamepadButtonType::RightTrigger2)),
+            reload: gamepad_button_input.just_pressed(button(GamepadButtonType::West)),
+            switch_weapon,
+            ..default()
+        };
+        return;
+    }
+
+    let scroll: f32 = mouse_wheel_events.read().map(|event| event.y).sum();
+    let slot = SLOT_KEYS
+        .iter()
+        .position(|&key| keyboard_input.just_pressed(key));
+    let switch_weapon = if let Some(slot) = slot {
+        Some(WeaponSwitch::Slot(slot as u8))
+    } else if keyboard_input.any_pressed(ZOOM_MODIFIER_KEYS) || scroll == 0.0 {
+        None
+    } else if scroll > 0.0 {
+        Some(WeaponSwitch::Previous)
+    } else {
+        Some(WeaponSwitch::Next)
+    };
+
+    *input = PlayerInput {
+        up: keyboard_input.any_pressed([KeyCode::KeyW, KeyCode::ArrowUp]),
+        down: keyboard_input.any_pressed([KeyCode::KeyS, KeyCode::ArrowDown]),
+        left: keyboard_input.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]),
+        right: keyboard_input.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]),
+        move_axis: None,
+        aim: None,
+        fire: mouse_button_input.pressed(MouseButton::Left),
+        reload: keyboard_input.just_pressed(KeyCode::KeyR),
+        switch_weapon,
+    };
+}
+
+/// Left and right stick positions of `gamepad`, y up.
+fn sticks(axes: &Axis<GamepadAxis>, gamepad: Gamepad) -> [Vec2; 2] {
+    let axis = |axis_type| {
+        axes.get(GamepadAxis::new(gamepad, axis_type))
+            .unwrap_or(0.0)
+    };
+    [
+        vec2(
+            axis(GamepadAxisType::LeftStickX),
+            axis(GamepadAxisType::LeftStickY),
+        ),
+        vec2(
+            axis(GamepadAxisType::RightStickX),
+            axis(GamepadAxisType::RightStickY),
+        ),
+    ]
+}
@@ -1,13 +1,33 @@
+pub mod aim;
 pub mod animation;
+pub mod boss;
+pub mod boss_def;
 pub mod camera;
+pub mod charger;
+pub mod cli;
 pub mod collision;
 pub mod configs;
+pub mod elite;
 pub mod enemy;
+pub mod enemy_def;
+pub mod game_config;
 pub mod gui;
 pub mod gun;
+pub mod headless;
+pub mod high_score;
+pub mod input;
+pub mod pause;
+pub mod pickup;
 pub mod player;
+pub mod projectile;
+pub mod ranged;
+pub mod replay;
 pub mod resources;
+pub mod rng;
+pub mod ron_asset;
 pub mod state;
+pub mod stats;
+pub mod weapon;
 pub mod world;
 
 pub use configs::*;
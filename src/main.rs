@@ -1,20 +1,52 @@
 use bevy::prelude::*;
-use std::env;
 
+use bevy_pancam::PanCamPlugin;
 use bevy_rapier2d::prelude::*;
+use infinite_shooter_game::aim::AimPlugin;
 use infinite_shooter_game::animation::AnimationPlugin;
+use infinite_shooter_game::boss::BossPlugin;
+use infinite_shooter_game::boss_def::BossDefPlugin;
 use infinite_shooter_game::camera::FollowCameraPlugin;
+use infinite_shooter_game::charger::ChargerPlugin;
+use infinite_shooter_game::cli::CliArgs;
 use infinite_shooter_game::collision::CollisionPlugin;
+use infinite_shooter_game::elite::ElitePlugin;
 use infinite_shooter_game::enemy::EnemyPlugin;
+use infinite_shooter_game::enemy_def::EnemyDefPlugin;
+use infinite_shooter_game::game_config::GameConfigPlugin;
 use infinite_shooter_game::gui::GuiPlugin;
 use infinite_shooter_game::gun::GunPlugin;
+use infinite_shooter_game::high_score::HighScorePlugin;
+use infinite_shooter_game::input::PlayerInputPlugin;
+use infinite_shooter_game::pause::PausePlugin;
+use infinite_shooter_game::pickup::PickupPlugin;
 use infinite_shooter_game::player::PlayerPlugin;
+use infinite_shooter_game::projectile::ProjectilePlugin;
+use infinite_shooter_game::ranged::RangedEnemyPlugin;
+use infinite_shooter_game::replay::{Replay, ReplayMode, ReplayPlugin};
+use infinite_shooter_game::rng::RngPlugin;
+use infinite_shooter_game::state::GameState;
+use infinite_shooter_game::stats::RunStatsPlugin;
+use infinite_shooter_game::weapon::WeaponPlugin;
 use infinite_shooter_game::world::WorldPlugin;
 use infinite_shooter_game::*;
 
 fn main() {
-    // env::set_var("RUST_BACKTRACE", "1"); // Uncomment to enable backtrace
-    App::new()
+    // std::env::set_var("RUST_BACKTRACE", "1"); // Uncomment to enable backtrace
+    let args = CliArgs::parse().unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(2);
+    });
+    let replay = args.replay.as_ref().map(|path| {
+        Replay::load(path).unwrap_or_else(|err| {
+            eprintln!("Failed to load replay {}: {err}", path.display());
+            std::process::exit(2);
+        })
+    });
+    let seed = replay.as_ref().map(|replay| replay.seed).or(args.seed);
+
+    let mut app = App::new();
+    app
         // .init_state::<GameState>()
         .add_plugins(
             DefaultPlugins
@@ -31,20 +63,48 @@ fn main() {
                 }),
         )
         .insert_resource(ClearColor(Color::srgb(BG_COLOR.0, BG_COLOR.1, BG_COLOR.2)))
+        .add_plugins(GameConfigPlugin)
+        .add_plugins(RngPlugin { seed })
+        .add_plugins(PlayerInputPlugin)
         .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
         .add_plugins(RapierDebugRenderPlugin::default()) // Display collision box
+        .add_plugins(PanCamPlugin)
         .add_plugins(FollowCameraPlugin)
         .add_plugins(GuiPlugin)
+        .add_plugins(WeaponPlugin)
+        .add_plugins(AimPlugin)
         .add_plugins(GunPlugin)
+        .add_plugins(ProjectilePlugin)
         .add_plugins(PlayerPlugin)
         .add_plugins(AnimationPlugin)
         .add_plugins(ResourcesPlugin)
         .add_plugins(WorldPlugin)
+        .add_plugins(EnemyDefPlugin)
         .add_plugins(EnemyPlugin)
+        .add_plugins(RangedEnemyPlugin)
+        .add_plugins(ChargerPlugin)
+        .add_plugins(ElitePlugin)
+        .add_plugins(BossDefPlugin)
+        .add_plugins(BossPlugin)
+        .add_plugins(PickupPlugin)
         .add_plugins(CollisionPlugin)
+        .add_plugins(PausePlugin)
+        .add_plugins(RunStatsPlugin)
+        .add_plugins(HighScorePlugin::default())
         .insert_resource(Msaa::Off)
-        .add_systems(Update, close_on_esc)
-        .run();
+        .add_systems(Update, close_on_esc.run_if(in_state(GameState::MainMenu)));
+
+    if let Some(replay) = replay {
+        app.add_plugins(ReplayPlugin {
+            mode: ReplayMode::Playback(replay),
+        });
+    } else if let Some(path) = args.record {
+        app.add_plugins(ReplayPlugin {
+            mode: ReplayMode::Record { path: Some(path) },
+        });
+    }
+
+    app.run();
 }
 
 pub fn close_on_esc(
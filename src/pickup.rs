@@ -0,0 +1,112 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::game_config::GameConfig;
+use crate::gun::WeaponInventory;
+use crate::player::{Health, Player};
+use crate::state::GameState;
+use crate::stats::RunStats;
+use crate::weapon::WeaponDef;
+use crate::world::GameEntity;
+use crate::*;
+
+pub struct PickupPlugin;
+
+/// Given to the player when they walk over a pickup holding it.
+#[derive(Component, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Reward {
+    /// Heals, up to `player.health`.
+    Health(f32),
+    /// Refills the magazine and reserve of every carried weapon.
+    Ammo,
+    Score(u32),
+}
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, collect_pickups.run_if(in_state(GameState::InGame)));
+    }
+}
+
+/// Spawns a pickup for each reward, spread around `position`.
+pub fn drop_rewards(
+    commands: &mut Commands,
+    handle: &GlobalTextureAtlas,
+    position: Vec2,
+    rewards: &[Reward],
+) {
+    for (i, reward) in rewards.iter().enumerate() {
+        let offset = if rewards.len() == 1 {
+            Vec2::ZERO
+        } else {
+            Vec2::from_angle(i as f32 * PI * 2.0 / rewards.len() as f32) * PICKUP_DROP_SPREAD
+        };
+        let (r, g, b) = reward.color();
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(r, g, b),
+                    ..default()
+                },
+                texture: handle.image.clone().unwrap(),
+                transform: Transform::from_translation((position + offset).extend(0.5))
+                    .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR * PICKUP_SCALE)),
+                ..default()
+            },
+            TextureAtlas {
+                layout: handle.layout.clone().unwrap(),
+                index: PICKUP_SPRITE_INDEX,
+            },
+            *reward,
+            GameEntity,
+        ));
+    }
+}
+
+fn collect_pickups(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    weapon_defs: Res<Assets<WeaponDef>>,
+    mut stats: ResMut<RunStats>,
+    mut player_query: Query<(&Transform, &mut Health, &mut WeaponInventory), With<Player>>,
+    pickup_query: Query<(&Transform, &Reward, Entity), Without<Player>>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let (player_transform, mut health, mut inventory) = player_query.single_mut();
+    let player_pos = player_transform.translation.truncate();
+    for (transform, reward, entity) in pickup_query.iter() {
+        if transform.translation.truncate().distance(player_pos) > PICKUP_RADIUS {
+            continue;
+        }
+
+        match reward {
+            Reward::Health(amount) => {
+                health.0 = (health.0 + amount).min(config.player.health.max(health.0));
+            }
+            Reward::Ammo => {
+                for slot in inventory.slots.iter_mut() {
+                    if let Some(def) = weapon_defs.get(&slot.weapon) {
+                        slot.refill(def);
+                    }
+                }
+            }
+            Reward::Score(score) => stats.score += *score as u64,
+        }
+        commands.entity(entity).despawn();
+    }
+}
+
+impl Reward {
+    fn color(&self) -> (f32, f32, f32) {
+        match self {
+            Reward::Health(_) => HEALTH_PICKUP_COLOR,
+            Reward::Ammo => AMMO_PICKUP_COLOR,
+            Reward::Score(_) => SCORE_PICKUP_COLOR,
+        }
+    }
+}
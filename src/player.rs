@@ -4,10 +4,14 @@ use bevy::time::Stopwatch;
 use bevy_rapier2d::prelude::*;
 
 use crate::state::GameState;
+use crate::stats::RunStats;
 use crate::*;
 
 use crate::animation::AnimationTimer;
-use crate::gun::{Gun, GunTimer};
+use crate::game_config::GameConfig;
+use crate::gun::{Gun, GunTimer, WeaponInventory, WeaponSlot};
+use crate::input::{PlayerInput, PlayerInputSet};
+use crate::weapon::{WeaponDef, Weapons};
 use crate::world::GameEntity;
 
 pub struct PlayerPlugin;
@@ -24,8 +28,11 @@ pub enum PlayerState {
     Run,
 }
 
+/// Damage to the player from touching an enemy or an enemy projectile.
 #[derive(Event)]
-pub struct PlayerEnemyCollisionEvent;
+pub struct PlayerEnemyCollisionEvent {
+    pub damage: f32,
+}
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
@@ -35,7 +42,7 @@ impl Plugin for PlayerPlugin {
                 Update,
                 (
                     handle_player_death,
-                    handle_player_input,
+                    handle_player_input.after(PlayerInputSet),
                     handle_player_enemy_collision_events,
                 )
                     .run_if(in_state(GameState::InGame)),
@@ -45,9 +52,34 @@ impl Plugin for PlayerPlugin {
 
 fn spawn_player(
     mut commands: Commands,
+    config: Res<GameConfig>,
     handle: Res<GlobalTextureAtlas>,
+    weapons: Res<Weapons>,
+    weapon_defs: Res<Assets<WeaponDef>>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
+    let inventory = WeaponInventory {
+        slots: config
+            .gun
+            .weapons
+            .iter()
+            .map(|name| {
+                let weapon = weapons
+                    .get(name)
+                    .expect("gun.weapons is validated against the built-in weapons");
+                let def = weapon_defs
+                    .get(&weapon)
+                    .expect("weapons are loaded before a run starts");
+                WeaponSlot::new(weapon, def)
+            })
+            .collect(),
+        active: 0,
+    };
+    let weapon = inventory.active_slot().weapon.clone();
+    let gun_sprite_index = weapon_defs
+        .get(&weapon)
+        .map_or(9, |weapon| weapon.gun_sprite_index);
+
     commands.spawn((
         SpriteBundle {
             texture: handle.image.clone().unwrap(),
@@ -59,7 +91,8 @@ fn spawn_player(
             index: 0,
         },
         Player,
-        Health(PLAYER_HEALTH),
+        Health(config.player.health),
+        inventory,
         PlayerState::default(),
         AnimationTimer(Timer::from_seconds(0.15, TimerMode::Repeating)),
         GameEntity,
@@ -80,9 +113,12 @@ fn spawn_player(
         },
         TextureAtlas {
             layout: handle.layout.clone().unwrap(),
-            index: 9,
+            index: gun_sprite_index,
+        },
+        Gun {
+            weapon,
+            reload: None,
         },
-        Gun,
         GunTimer(Stopwatch::new()),
         GameEntity,
     ));
@@ -91,6 +127,7 @@ fn spawn_player(
 }
 
 fn handle_player_enemy_collision_events(
+    mut stats: ResMut<RunStats>,
     mut player_query: Query<&mut Health, With<Player>>,
     mut events: EventReader<PlayerEnemyCollisionEvent>,
 ) {
@@ -99,8 +136,9 @@ fn handle_player_enemy_collision_events(
     }
 
     let mut health = player_query.single_mut();
-    for _ in events.read() {
-        health.0 -= ENEMY_DAMAGE;
+    for event in events.read() {
+        health.0 -= event.damage;
+        stats.damage_taken += event.damage;
     }
 }
 
@@ -113,7 +151,7 @@ fn handle_player_death(
     }
     let health = player_query.single();
     if health.0 <= 0.0 {
-        next_state.set(GameState::MainMenu);
+        next_state.set(GameState::GameOver);
     }
 }
 
@@ -151,19 +189,20 @@ fn handle_player_death(
 // }
 
 fn handle_player_input(
+    time: Res<Time>,
+    config: Res<GameConfig>,
     mut player_query: Query<(&mut Transform, &mut PlayerState, &mut Velocity), With<Player>>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    input: Res<PlayerInput>,
 ) {
     if player_query.is_empty() {
         return;
     }
 
     let (mut transform, mut player_state, mut velocity) = player_query.single_mut();
-    let w_key = keyboard_input.pressed(KeyCode::KeyW) || keyboard_input.pressed(KeyCode::ArrowUp);
-    let a_key = keyboard_input.pressed(KeyCode::KeyA) || keyboard_input.pressed(KeyCode::ArrowLeft);
-    let s_key = keyboard_input.pressed(KeyCode::KeyS) || keyboard_input.pressed(KeyCode::ArrowDown);
-    let d_key =
-        keyboard_input.pressed(KeyCode::KeyD) || keyboard_input.pressed(KeyCode::ArrowRight);
+    let w_key = input.up;
+    let a_key = input.left;
+    let s_key = input.down;
+    let d_key = input.right;
 
     let mut delta = Vec2::ZERO;
     if w_key {
@@ -179,11 +218,18 @@ fn handle_player_input(
         delta.x += 1.0;
     }
     delta = delta.normalize();
+    let mut moving = w_key || a_key || s_key || d_key;
+    // A gamepad stick moves slower when only pushed part of the way.
+    if let Some(move_axis) = input.move_axis {
+        delta = move_axis;
+        moving = move_axis != Vec2::ZERO;
+    }
 
     velocity.linvel = Vec2::ZERO; // Set linear velocity to zero
 
-    if delta.is_finite() && (w_key || a_key || s_key || d_key) {
-        transform.translation += vec3(delta.x, delta.y, 0.0) * PLAYER_SPEED;
+    if delta.is_finite() && moving {
+        transform.translation +=
+            vec3(delta.x, delta.y, 0.0) * config.player.speed * time.delta_seconds();
         transform.translation.z = 10.0;
         *player_state = PlayerState::Run;
     } else {
@@ -0,0 +1,392 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+use crate::collision::{collider_radius, handle_enemy_bullet_collision, EnemyKdTree};
+use crate::elite::Armor;
+use crate::enemy::{DamageSource, Enemy};
+use crate::game_config::{non_negative, positive, ConfigError, GameConfig, GameConfigReloaded};
+use crate::gun::{update_bullets, Bullet, BulletVelocity, Pierce};
+use crate::state::GameState;
+use crate::*;
+
+pub struct ProjectilePlugin;
+
+/// Behaviour added to every projectile a weapon fires. Modifiers from several
+/// sources (a weapon and its upgrades) are merged with `combine`.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectileModifiers {
+    /// Enemies a projectile passes through before it is used up.
+    pub pierce: u32,
+    pub homing: Option<Homing>,
+    pub explosive: Option<Explosive>,
+    /// Times a projectile bounces off obstacles before it stops.
+    pub ricochet: u32,
+}
+
+/// Steers the projectile toward the nearest enemy within `range`.
+#[derive(Component, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Homing {
+    pub turn_rate_degrees: f32,
+    pub range: f32,
+}
+
+/// Damages every enemy within `radius` of the impact point. Piercing
+/// projectiles explode once, on the hit that uses them up.
+#[derive(Component, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Explosive {
+    pub radius: f32,
+    pub damage: f32,
+}
+
+#[derive(Component)]
+pub struct Ricochet {
+    pub remaining: u32,
+    /// Set when the projectile was moved to the obstacle this frame, so
+    /// `update_bullets` leaves it there instead of moving it through.
+    pub bounced: bool,
+}
+
+/// A projectile reached a static obstacle and either bounced off or stopped.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ProjectileImpact {
+    pub projectile: Entity,
+    pub obstacle: Entity,
+    pub point: Vec2,
+    pub normal: Vec2,
+    pub ricocheted: bool,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Explosion {
+    pub position: Vec2,
+    pub radius: f32,
+    pub damage: f32,
+}
+
+/// Hidden projectile entities waiting to be reused, so firing doesn't spawn
+/// and despawn whole sprite entities every shot.
+#[derive(Resource)]
+pub struct ProjectilePool {
+    free: Vec<Entity>,
+    size: usize,
+    /// Entities owned by the pool, in flight or free.
+    pooled: usize,
+}
+
+/// Marks projectiles owned by the pool, which are hidden on release instead
+/// of despawned.
+#[derive(Component)]
+struct Pooled;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ProjectileImpact>()
+            .add_event::<Explosion>()
+            .init_resource::<ProjectilePool>()
+            .add_systems(OnEnter(GameState::GameInit), reset_projectile_pool)
+            .add_systems(
+                Update,
+                resize_projectile_pool.run_if(on_event::<GameConfigReloaded>()),
+            )
+            .add_systems(
+                Update,
+                (
+                    (steer_homing_projectiles, collide_with_obstacles)
+                        .chain()
+                        .before(update_bullets),
+                    apply_explosions
+                        .after(handle_enemy_bullet_collision)
+                        .after(collide_with_obstacles),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Pooled entities are despawned with the rest of the run.
+fn reset_projectile_pool(mut commands: Commands, config: Res<GameConfig>) {
+    commands.insert_resource(ProjectilePool::new(config.gun.projectile_pool_size));
+}
+
+/// Applies a changed `gun.projectile_pool_size`. Free entities past the new
+/// size are despawned now, ones in flight when they are released.
+fn resize_projectile_pool(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mut pool: ResMut<ProjectilePool>,
+) {
+    pool.size = config.gun.projectile_pool_size;
+    while pool.pooled > pool.size {
+        let Some(entity) = pool.free.pop() else {
+            break;
+        };
+        commands.entity(entity).despawn();
+        pool.pooled -= 1;
+    }
+}
+
+fn steer_homing_projectiles(
+    time: Res<Time>,
+    tree: Res<EnemyKdTree>,
+    enemy_query: Query<&Transform, With<Enemy>>,
+    mut bullet_query: Query<(&Transform, &Homing, &mut BulletVelocity), With<Bullet>>,
+) {
+    for (transform, homing, mut velocity) in bullet_query.iter_mut() {
+        let pos = transform.translation.truncate();
+        let Some(target) = tree.nearest_within(pos, homing.range) else {
+            continue;
+        };
+        let Ok(target_transform) = enemy_query.get(target) else {
+            continue;
+        };
+
+        let heading = velocity.0.truncate();
+        let to_target = target_transform.translation.truncate() - pos;
+        let max_turn = homing.turn_rate_degrees.to_radians() * time.delta_seconds();
+        let turn = heading.angle_between(to_target).clamp(-max_turn, max_turn);
+        if turn.is_finite() {
+            velocity.0 = Vec2::from_angle(turn).rotate(heading).extend(velocity.0.z);
+        }
+    }
+}
+
+/// Casts each projectile's motion for this frame against the static
+/// obstacles. Projectiles with bounces left are reflected, the rest stop there.
+#[allow(clippy::type_complexity)]
+fn collide_with_obstacles(
+    mut commands: Commands,
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    mut bullet_query: Query<
+        (
+            &mut Transform,
+            &mut BulletVelocity,
+            Option<&mut Ricochet>,
+            Option<&Explosive>,
+            Entity,
+        ),
+        With<Bullet>,
+    >,
+    mut impacts: EventWriter<ProjectileImpact>,
+    mut explosions: EventWriter<Explosion>,
+) {
+    for (mut transform, mut velocity, ricochet, explosive, bullet) in bullet_query.iter_mut() {
+        let pos = transform.translation.truncate();
+        let motion = velocity.0.truncate() * time.delta_seconds();
+        let distance = motion.length();
+        if distance == 0.0 {
+            continue;
+        }
+
+        let Some((obstacle, hit)) = rapier_context.cast_ray_and_get_normal(
+            pos,
+            motion / distance,
+            distance,
+            true,
+            QueryFilter::only_fixed(),
+        ) else {
+            continue;
+        };
+
+        // A zero normal means the projectile started inside the obstacle.
+        let ricocheted = match ricochet {
+            Some(mut ricochet) if ricochet.remaining > 0 && hit.normal != Vec2::ZERO => {
+                ricochet.remaining -= 1;
+                ricochet.bounced = true;
+                true
+            }
+            _ => false,
+        };
+        impacts.send(ProjectileImpact {
+            projectile: bullet,
+            obstacle,
+            point: hit.point,
+            normal: hit.normal,
+            ricocheted,
+        });
+
+        if ricocheted {
+            let v = velocity.0.truncate();
+            let reflected = v - 2.0 * v.dot(hit.normal) * hit.normal;
+            velocity.0 = reflected.extend(velocity.0.z);
+            transform.translation =
+                (hit.point + hit.normal * RICOCHET_OFFSET).extend(transform.translation.z);
+            continue;
+        }
+
+        if let Some(explosive) = explosive {
+            explosions.send(Explosion {
+                position: hit.point,
+                radius: explosive.radius,
+                damage: explosive.damage,
+            });
+        }
+        release_projectile(&mut commands, bullet);
+    }
+}
+
+pub fn apply_explosions(
+    tree: Res<EnemyKdTree>,
+    mut enemy_query: Query<(&Transform, &Collider, &mut Enemy, Option<&Armor>)>,
+    mut events: EventReader<Explosion>,
+) {
+    for explosion in events.read() {
+        for entity in
+            tree.within_radius(explosion.position, explosion.radius + KD_TREE_QUERY_MARGIN)
+        {
+            let Ok((transform, collider, mut enemy, armor)) = enemy_query.get_mut(entity) else {
+                continue;
+            };
+
+            let dist = transform
+                .translation
+                .truncate()
+                .distance(explosion.position);
+            if dist <= explosion.radius + collider_radius(collider, transform) {
+                enemy.health -=
+                    armor.map_or(explosion.damage, |armor| armor.reduce(explosion.damage));
+                enemy.last_hit_by = Some(DamageSource::Explosion);
+            }
+        }
+    }
+}
+
+impl ProjectileModifiers {
+    /// Stacks pierce and ricochet, and keeps the stronger homing and explosion.
+    pub fn combine(&self, other: &Self) -> Self {
+        Self {
+            pierce: self.pierce + other.pierce,
+            homing: match (self.homing, other.homing) {
+                (Some(a), Some(b)) => Some(Homing {
+                    turn_rate_degrees: a.turn_rate_degrees.max(b.turn_rate_degrees),
+                    range: a.range.max(b.range),
+                }),
+                (a, b) => a.or(b),
+            },
+            explosive: match (self.explosive, other.explosive) {
+                (Some(a), Some(b)) => Some(Explosive {
+                    radius: a.radius.max(b.radius),
+                    damage: a.damage.max(b.damage),
+                }),
+                (a, b) => a.or(b),
+            },
+            ricochet: self.ricochet + other.ricochet,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if let Some(homing) = &self.homing {
+            non_negative(
+                "modifiers.homing.turn_rate_degrees",
+                homing.turn_rate_degrees,
+            )?;
+            positive("modifiers.homing.range", homing.range)?;
+        }
+        if let Some(explosive) = &self.explosive {
+            positive("modifiers.explosive.radius", explosive.radius)?;
+            non_negative("modifiers.explosive.damage", explosive.damage)?;
+        }
+        Ok(())
+    }
+
+    pub fn insert_into(&self, projectile: &mut EntityCommands) {
+        projectile.insert(Pierce {
+            remaining: self.pierce,
+            hit: Vec::new(),
+        });
+        if let Some(homing) = self.homing {
+            projectile.insert(homing);
+        }
+        if let Some(explosive) = self.explosive {
+            projectile.insert(explosive);
+        }
+        if self.ricochet > 0 {
+            projectile.insert(Ricochet {
+                remaining: self.ricochet,
+                bounced: false,
+            });
+        }
+    }
+}
+
+impl ProjectilePool {
+    pub fn new(size: usize) -> Self {
+        Self {
+            free: Vec::with_capacity(size),
+            size,
+            pooled: 0,
+        }
+    }
+
+    /// Reuses a free projectile entity for `bundle`, or spawns a new one.
+    /// Once the pool is full, extra projectiles are despawned on release.
+    pub fn spawn<'a>(
+        &mut self,
+        commands: &'a mut Commands,
+        bundle: impl Bundle,
+    ) -> EntityCommands<'a> {
+        if let Some(entity) = self.free.pop() {
+            let mut projectile = commands.entity(entity);
+            projectile.insert(bundle);
+            projectile
+        } else if self.pooled < self.size {
+            self.pooled += 1;
+            commands.spawn((bundle, Pooled))
+        } else {
+            commands.spawn(bundle)
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Entities ready to be reused.
+    pub fn free(&self) -> usize {
+        self.free.len()
+    }
+}
+
+impl Default for ProjectilePool {
+    fn default() -> Self {
+        Self::new(PROJECTILE_POOL_SIZE)
+    }
+}
+
+/// Takes `projectile` out of play, returning it to the pool when it belongs
+/// there. Releasing it more than once a frame is harmless.
+pub fn release_projectile(commands: &mut Commands, projectile: Entity) {
+    commands.add(move |world: &mut World| {
+        let Some(entity) = world.get_entity_mut(projectile) else {
+            return;
+        };
+        if !entity.contains::<Bullet>() {
+            return;
+        }
+        if !entity.contains::<Pooled>() {
+            entity.despawn();
+            return;
+        }
+
+        let mut pool = world.resource_mut::<ProjectilePool>();
+        if pool.pooled > pool.size {
+            // The pool shrank while it was in flight.
+            pool.pooled -= 1;
+            world.despawn(projectile);
+            return;
+        }
+
+        pool.free.push(projectile);
+        // Components every projectile gets are overwritten on reuse, so only
+        // `Bullet` and the optional modifiers need to go.
+        world
+            .entity_mut(projectile)
+            .remove::<(Bullet, Homing, Explosive, Ricochet)>()
+            .insert(Visibility::Hidden);
+    });
+}
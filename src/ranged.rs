@@ -0,0 +1,256 @@
+use bevy::prelude::*;
+use bevy::utils::Duration;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use crate::collision::collider_radius;
+use crate::elite::{Elite, Haste};
+use crate::enemy_def::RangedDef;
+use crate::game_config::GameConfig;
+use crate::player::{Player, PlayerEnemyCollisionEvent};
+use crate::state::GameState;
+use crate::world::GameEntity;
+use crate::*;
+
+pub struct RangedEnemyPlugin;
+
+/// Added to enemies spawned with `EnemyBehaviour::Ranged`, which keep their
+/// distance and shoot instead of chasing the player. Their speed and ranged
+/// stats are copied at spawn, so enemy definition edits only reach new ones.
+#[derive(Component)]
+pub struct RangedState {
+    def: RangedDef,
+    speed: f32,
+    /// 1 or -1, the way the enemy circles the player.
+    strafe_sign: f32,
+    cooldown: Timer,
+    telegraph: Option<Timer>,
+}
+
+/// Fired by ranged enemies. Hurts the player, never other enemies.
+#[derive(Component)]
+pub struct EnemyProjectile {
+    pub damage: f32,
+    pub velocity: Vec2,
+    lifetime: Timer,
+}
+
+impl Plugin for RangedEnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                move_ranged_enemies,
+                update_ranged_attacks,
+                update_enemy_projectiles,
+            )
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+fn move_ranged_enemies(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    player_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<(&mut Transform, &RangedState, Option<&Haste>), Without<Player>>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let player_pos = player_query.single().translation.truncate();
+    for (mut transform, state, haste) in enemy_query.iter_mut() {
+        let ranged = &state.def;
+        let speed = state.speed;
+        if state.telegraph.is_some() {
+            continue;
+        }
+
+        let offset = transform.translation.truncate() - player_pos;
+        let distance = offset.length();
+        let Some(away) = offset.try_normalize() else {
+            continue;
+        };
+        let radial = if distance > ranged.preferred_distance + RANGED_DISTANCE_TOLERANCE {
+            -speed
+        } else if distance < ranged.preferred_distance - RANGED_DISTANCE_TOLERANCE {
+            speed
+        } else {
+            0.0
+        };
+        let strafe = ranged.strafe_speed * state.strafe_sign;
+        let haste = haste.map_or(1.0, |haste| haste.0);
+        let velocity = (away * radial + away.perp() * strafe) * config.enemy.speed_scale * haste;
+        transform.translation += velocity.extend(0.0) * time.delta_seconds();
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn update_ranged_attacks(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    handle: Res<GlobalTextureAtlas>,
+    player_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<
+        (&Transform, &mut RangedState, &mut Sprite, Option<&Elite>),
+        Without<Player>,
+    >,
+    projectile_query: Query<(), With<EnemyProjectile>>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let player_pos = player_query.single().translation.truncate();
+    let mut num_projectiles = projectile_query.iter().len();
+    for (transform, mut state, mut sprite, elite) in enemy_query.iter_mut() {
+        let RangedState {
+            def: ranged,
+            cooldown,
+            telegraph,
+            ..
+        } = &mut *state;
+        let pos = transform.translation.truncate();
+
+        let Some(timer) = telegraph.as_mut() else {
+            cooldown.tick(time.delta());
+            let in_range = pos.distance(player_pos) <= ranged.fire_range;
+            if cooldown.finished() && in_range && num_projectiles < config.enemy.max_projectiles {
+                let (r, g, b) = TELEGRAPH_COLOR;
+                sprite.color = Color::srgb(r, g, b);
+                *telegraph = Some(Timer::from_seconds(ranged.telegraph_secs, TimerMode::Once));
+            }
+            continue;
+        };
+        if !timer.tick(time.delta()).finished() {
+            continue;
+        }
+
+        sprite.color = elite.map_or(Color::WHITE, Elite::color);
+        *telegraph = None;
+        *cooldown = Timer::from_seconds(ranged.fire_interval_secs, TimerMode::Once);
+        let Some(dir) = (player_pos - pos).try_normalize() else {
+            continue;
+        };
+        if num_projectiles >= config.enemy.max_projectiles {
+            continue;
+        }
+        num_projectiles += 1;
+
+        spawn_enemy_projectile(
+            &mut commands,
+            &handle,
+            pos,
+            dir * ranged.projectile_speed,
+            ranged.projectile_damage * config.enemy.damage_scale,
+            ranged.projectile_lifetime_secs,
+        );
+    }
+}
+
+pub(crate) fn spawn_enemy_projectile(
+    commands: &mut Commands,
+    handle: &GlobalTextureAtlas,
+    position: Vec2,
+    velocity: Vec2,
+    damage: f32,
+    lifetime_secs: f32,
+) {
+    let (r, g, b) = ENEMY_PROJECTILE_COLOR;
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgb(r, g, b),
+                ..default()
+            },
+            texture: handle.image.clone().unwrap(),
+            transform: Transform::from_translation(position.extend(1.0))
+                .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
+            ..default()
+        },
+        TextureAtlas {
+            layout: handle.layout.clone().unwrap(),
+            index: ENEMY_PROJECTILE_SPRITE_INDEX,
+        },
+        EnemyProjectile {
+            damage,
+            velocity,
+            lifetime: Timer::from_seconds(lifetime_secs, TimerMode::Once),
+        },
+        GameEntity,
+    ));
+}
+
+fn update_enemy_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    player_query: Query<(&Transform, &Collider), With<Player>>,
+    mut projectile_query: Query<(&mut Transform, &mut EnemyProjectile, Entity), Without<Player>>,
+    mut ew: EventWriter<PlayerEnemyCollisionEvent>,
+) {
+    let player = player_query.get_single().ok().map(|(transform, collider)| {
+        let radius = collider_radius(collider, transform) + ENEMY_PROJECTILE_RADIUS;
+        (transform.translation.truncate(), radius)
+    });
+
+    for (mut transform, mut projectile, entity) in projectile_query.iter_mut() {
+        if projectile.lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let pos = transform.translation.truncate();
+        let motion = projectile.velocity * time.delta_seconds();
+        let distance = motion.length();
+        if distance > 0.0
+            && rapier_context
+                .cast_ray(
+                    pos,
+                    motion / distance,
+                    distance,
+                    true,
+                    QueryFilter::only_fixed(),
+                )
+                .is_some()
+        {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation += motion.extend(0.0);
+
+        if let Some((player_pos, radius)) = player {
+            if transform.translation.truncate().distance(player_pos) <= radius {
+                ew.send(PlayerEnemyCollisionEvent {
+                    damage: projectile.damage,
+                });
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+impl RangedState {
+    /// Starts partway through the cooldown so enemies spawned together
+    /// don't all shoot at once.
+    pub fn new(def: &RangedDef, speed: f32, rng: &mut impl Rng) -> Self {
+        let strafe_sign = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+        let mut cooldown = Timer::from_seconds(def.fire_interval_secs, TimerMode::Once);
+        cooldown.set_elapsed(Duration::from_secs_f32(
+            rng.gen_range(0.0..def.fire_interval_secs),
+        ));
+        Self {
+            def: def.clone(),
+            speed,
+            strafe_sign,
+            cooldown,
+            telegraph: None,
+        }
+    }
+
+    pub fn is_telegraphing(&self) -> bool {
+        self.telegraph.is_some()
+    }
+}
@@ -0,0 +1,404 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bevy::ecs::schedule::ExecutorKind;
+use bevy::prelude::*;
+use bevy::time::{TimeSystem, TimeUpdateStrategy};
+use bevy::utils::Duration;
+
+use crate::input::{PlayerInput, PlayerInputSet, WeaponSwitch};
+use crate::rng::GameRng;
+use crate::state::{GameState, InRun};
+use crate::*;
+
+pub struct ReplayPlugin {
+    pub mode: ReplayMode,
+}
+
+pub enum ReplayMode {
+    /// Record every run, writing it to `path` (when set) as soon as the run ends.
+    Record { path: Option<PathBuf> },
+    /// Play back a single recorded run in place of the input devices.
+    Playback(Replay),
+}
+
+/// One recorded run: the RNG seed plus the input and frame time of every
+/// `GameState::InGame` frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub frames: Vec<ReplayFrame>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayFrame {
+    /// Game frame time, which is zero on the first frame after a pause.
+    /// Playback feeds it back as the real time step, at a relative speed of
+    /// 1 since it is already scaled.
+    pub delta: Duration,
+    pub input: PlayerInput,
+    pub cursor: Option<Vec2>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    NotAReplay,
+    UnsupportedVersion(u16),
+    Truncated,
+}
+
+#[derive(Resource)]
+pub struct ReplayRecorder {
+    path: Option<PathBuf>,
+    replay: Option<Replay>,
+}
+
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    replay: Replay,
+    next_frame: usize,
+    resume_time_update: Option<TimeUpdateStrategy>,
+    resume_relative_speed: Option<f32>,
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        // Ambiguously ordered systems must run in the same order in the
+        // recorded and the replayed session.
+        app.edit_schedule(Update, |schedule| {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        });
+
+        match &self.mode {
+            ReplayMode::Record { path } => {
+                app.insert_resource(ReplayRecorder {
+                    path: path.clone(),
+                    replay: None,
+                })
+                .add_systems(OnEnter(GameState::GameInit), start_recording)
+                .add_systems(
+                    Update,
+                    record_replay_frame
+                        .after(PlayerInputSet)
+                        .run_if(in_state(GameState::InGame)),
+                )
+                .add_systems(OnExit(InRun), save_recording)
+                .add_systems(Last, save_recording.run_if(on_event::<AppExit>()));
+            }
+            ReplayMode::Playback(replay) => {
+                app.insert_resource(ReplayPlayback {
+                    replay: replay.clone(),
+                    next_frame: 0,
+                    resume_time_update: None,
+                    resume_relative_speed: None,
+                })
+                .add_systems(
+                    OnEnter(GameState::MainMenu),
+                    start_playback.run_if(is_replaying),
+                )
+                .add_systems(
+                    Update,
+                    play_replay_frame
+                        .in_set(PlayerInputSet)
+                        .run_if(in_state(GameState::InGame))
+                        .run_if(is_replaying),
+                )
+                .add_systems(
+                    First,
+                    set_replay_frame_time
+                        .before(TimeSystem)
+                        .run_if(is_replaying),
+                )
+                .add_systems(Last, stop_playback.run_if(replay_finished))
+                .add_systems(OnExit(InRun), stop_playback.run_if(is_replaying));
+            }
+        }
+    }
+}
+
+pub fn is_replaying(playback: Option<Res<ReplayPlayback>>) -> bool {
+    playback.is_some()
+}
+
+fn replay_finished(playback: Option<Res<ReplayPlayback>>) -> bool {
+    playback.is_some_and(|playback| playback.is_finished())
+}
+
+fn start_recording(mut recorder: ResMut<ReplayRecorder>) {
+    recorder.replay = None;
+}
+
+fn record_replay_frame(
+    mut recorder: ResMut<ReplayRecorder>,
+    rng: Res<GameRng>,
+    time: Res<Time>,
+    input: Res<PlayerInput>,
+    cursor_pos: Res<CursorPosition>,
+) {
+    let replay = recorder.replay.get_or_insert_with(|| Replay {
+        seed: rng.seed(),
+        frames: Vec::new(),
+    });
+    replay.frames.push(ReplayFrame {
+        delta: time.delta(),
+        input: *input,
+        cursor: cursor_pos.0,
+    });
+}
+
+fn save_recording(recorder: Res<ReplayRecorder>) {
+    let (Some(path), Some(replay)) = (&recorder.path, &recorder.replay) else {
+        return;
+    };
+
+    match replay.save(path) {
+        Ok(()) => info!("Replay saved to {}", path.display()),
+        Err(err) => error!("Failed to save replay to {}: {err}", path.display()),
+    }
+}
+
+fn start_playback(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::GameInit);
+}
+
+fn play_replay_frame(
+    mut playback: ResMut<ReplayPlayback>,
+    mut input: ResMut<PlayerInput>,
+    mut cursor_pos: ResMut<CursorPosition>,
+) {
+    let Some(frame) = playback.replay.frames.get(playback.next_frame).copied() else {
+        return;
+    };
+
+    *input = frame.input;
+    cursor_pos.0 = frame.cursor;
+    playback.next_frame += 1;
+}
+
+/// Runs before `Time` updates, so each frame advances it by the recorded
+/// frame time.
+fn set_replay_frame_time(
+    mut playback: ResMut<ReplayPlayback>,
+    mut time_update_strategy: ResMut<TimeUpdateStrategy>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    let Some(frame) = playback.replay.frames.get(playback.next_frame) else {
+        return;
+    };
+
+    let frame_time = TimeUpdateStrategy::ManualDuration(frame.delta);
+    let previous = std::mem::replace(&mut *time_update_strategy, frame_time);
+    playback.resume_time_update.get_or_insert(previous);
+    let speed = virtual_time.relative_speed();
+    playback.resume_relative_speed.get_or_insert(speed);
+    virtual_time.set_relative_speed(1.0);
+}
+
+fn stop_playback(
+    mut commands: Commands,
+    mut playback: ResMut<ReplayPlayback>,
+    mut time_update_strategy: ResMut<TimeUpdateStrategy>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    info!(
+        "Replay finished after {} of {} frames",
+        playback.next_frame,
+        playback.replay.frames.len()
+    );
+    if let Some(resume_time_update) = playback.resume_time_update.take() {
+        *time_update_strategy = resume_time_update;
+    }
+    if let Some(speed) = playback.resume_relative_speed.take() {
+        virtual_time.set_relative_speed(speed);
+    }
+    commands.remove_resource::<ReplayPlayback>();
+}
+
+impl ReplayRecorder {
+    /// The run being recorded, or the last finished one.
+    pub fn replay(&self) -> Option<&Replay> {
+        self.replay.as_ref()
+    }
+}
+
+impl ReplayPlayback {
+    pub fn is_finished(&self) -> bool {
+        self.next_frame >= self.replay.frames.len()
+    }
+}
+
+const REPLAY_MAGIC: &[u8; 4] = b"ISGR";
+const REPLAY_VERSION: u16 = 3;
+
+const FLAG_UP: u8 = 1 << 0;
+const FLAG_DOWN: u8 = 1 << 1;
+const FLAG_LEFT: u8 = 1 << 2;
+const FLAG_RIGHT: u8 = 1 << 3;
+const FLAG_FIRE: u8 = 1 << 4;
+const FLAG_CURSOR: u8 = 1 << 5;
+const FLAG_RELOAD: u8 = 1 << 6;
+
+const GAMEPAD_MOVE_AXIS: u8 = 1 << 0;
+const GAMEPAD_AIM: u8 = 1 << 1;
+
+const SWITCH_NONE: u8 = 0;
+const SWITCH_NEXT: u8 = 0xFE;
+const SWITCH_PREVIOUS: u8 = 0xFF;
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        let bytes = fs::read(path).map_err(ReplayError::Io)?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        fs::write(path, self.to_bytes()).map_err(ReplayError::Io)
+    }
+
+    /// Little-endian header (magic, version, seed, frame count) followed by
+    /// each frame's time in nanoseconds, an input bitmask, a weapon switch, a
+    /// gamepad bitmask, the stick positions used and, when the cursor was
+    /// over the window, its world position.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(18 + self.frames.len() * 15);
+        bytes.extend_from_slice(REPLAY_MAGIC);
+        bytes.extend_from_slice(&REPLAY_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+
+        for frame in self.frames.iter() {
+            let input = frame.input;
+            let mut flags = 0;
+            for (pressed, flag) in [
+                (input.up, FLAG_UP),
+                (input.down, FLAG_DOWN),
+                (input.left, FLAG_LEFT),
+                (input.right, FLAG_RIGHT),
+                (input.fire, FLAG_FIRE),
+                (input.reload, FLAG_RELOAD),
+                (frame.cursor.is_some(), FLAG_CURSOR),
+            ] {
+                if pressed {
+                    flags |= flag;
+                }
+            }
+
+            let delta_nanos = frame.delta.as_nanos().min(u32::MAX as u128) as u32;
+            bytes.extend_from_slice(&delta_nanos.to_le_bytes());
+            bytes.push(flags);
+            bytes.push(match input.switch_weapon {
+                None => SWITCH_NONE,
+                Some(WeaponSwitch::Slot(slot)) => slot + 1,
+                Some(WeaponSwitch::Next) => SWITCH_NEXT,
+                Some(WeaponSwitch::Previous) => SWITCH_PREVIOUS,
+            });
+            let mut gamepad_flags = 0;
+            if input.move_axis.is_some() {
+                gamepad_flags |= GAMEPAD_MOVE_AXIS;
+            }
+            if input.aim.is_some() {
+                gamepad_flags |= GAMEPAD_AIM;
+            }
+            bytes.push(gamepad_flags);
+            for stick in [input.move_axis, input.aim].into_iter().flatten() {
+                bytes.extend_from_slice(&stick.x.to_le_bytes());
+                bytes.extend_from_slice(&stick.y.to_le_bytes());
+            }
+            if let Some(cursor) = frame.cursor {
+                bytes.extend_from_slice(&cursor.x.to_le_bytes());
+                bytes.extend_from_slice(&cursor.y.to_le_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReplayError> {
+        let mut reader = ByteReader(bytes);
+        if reader.take::<4>()? != *REPLAY_MAGIC {
+            return Err(ReplayError::NotAReplay);
+        }
+
+        let version = u16::from_le_bytes(reader.take()?);
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+
+        let seed = u64::from_le_bytes(reader.take()?);
+        let num_frames = u32::from_le_bytes(reader.take()?) as usize;
+        let mut frames = Vec::with_capacity(num_frames.min(bytes.len()));
+        for _ in 0..num_frames {
+            let delta = Duration::from_nanos(u32::from_le_bytes(reader.take()?) as u64);
+            let [flags] = reader.take()?;
+            let switch_weapon = match reader.take()? {
+                [SWITCH_NONE] => None,
+                [SWITCH_NEXT] => Some(WeaponSwitch::Next),
+                [SWITCH_PREVIOUS] => Some(WeaponSwitch::Previous),
+                [slot] => Some(WeaponSwitch::Slot(slot - 1)),
+            };
+            let [gamepad_flags] = reader.take()?;
+            let move_axis = reader.vec2_if(gamepad_flags & GAMEPAD_MOVE_AXIS != 0)?;
+            let aim = reader.vec2_if(gamepad_flags & GAMEPAD_AIM != 0)?;
+            let cursor = reader.vec2_if(flags & FLAG_CURSOR != 0)?;
+
+            frames.push(ReplayFrame {
+                delta,
+                input: PlayerInput {
+                    up: flags & FLAG_UP != 0,
+                    down: flags & FLAG_DOWN != 0,
+                    left: flags & FLAG_LEFT != 0,
+                    right: flags & FLAG_RIGHT != 0,
+                    move_axis,
+                    aim,
+                    fire: flags & FLAG_FIRE != 0,
+                    reload: flags & FLAG_RELOAD != 0,
+                    switch_weapon,
+                },
+                cursor,
+            });
+        }
+
+        Ok(Self { seed, frames })
+    }
+}
+
+struct ByteReader<'a>(&'a [u8]);
+
+impl ByteReader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], ReplayError> {
+        if self.0.len() < N {
+            return Err(ReplayError::Truncated);
+        }
+
+        let (head, tail) = self.0.split_at(N);
+        self.0 = tail;
+        Ok(head.try_into().unwrap())
+    }
+
+    fn vec2_if(&mut self, present: bool) -> Result<Option<Vec2>, ReplayError> {
+        if !present {
+            return Ok(None);
+        }
+
+        let x = f32::from_le_bytes(self.take()?);
+        let y = f32::from_le_bytes(self.take()?);
+        Ok(Some(Vec2::new(x, y)))
+    }
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(err) => write!(f, "{err}"),
+            ReplayError::NotAReplay => write!(f, "not a replay file"),
+            ReplayError::UnsupportedVersion(version) => {
+                write!(f, "unsupported replay version {version}")
+            }
+            ReplayError::Truncated => write!(f, "replay file is truncated"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
@@ -1,24 +1,30 @@
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 
-use crate::state::GameState;
+use crate::boss_def::BossDefs;
+use crate::enemy_def::EnemyDefs;
+use crate::input::PlayerInputSet;
+use crate::replay::is_replaying;
+use crate::ron_asset::RonAssetHandle;
+use crate::state::{GameState, InRun};
+use crate::weapon::Weapons;
 use crate::*;
 
 pub struct ResourcesPlugin;
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct GlobalTextureAtlas {
     pub layout: Option<Handle<TextureAtlasLayout>>,
     pub image: Option<Handle<Image>>,
 }
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct BigTreeTextureAtlas {
     pub layout: Option<Handle<TextureAtlasLayout>>,
     pub image: Option<Handle<Image>>,
 }
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct SmallTreeTextureAtlas {
     pub layout: Option<Handle<TextureAtlasLayout>>,
     pub image: Option<Handle<Image>>,
@@ -30,14 +36,19 @@ pub struct CursorPosition(pub Option<Vec2>);
 impl Plugin for ResourcesPlugin {
     fn build(&self, app: &mut App) {
         app.insert_state(GameState::Loading)
+            .add_computed_state::<InRun>()
             .insert_resource(GlobalTextureAtlas::default())
             .insert_resource(BigTreeTextureAtlas::default())
             .insert_resource(SmallTreeTextureAtlas::default())
             .insert_resource(CursorPosition(None))
             .add_systems(OnEnter(GameState::Loading), load_assets)
+            .add_systems(Update, finish_loading.run_if(in_state(GameState::Loading)))
             .add_systems(
                 Update,
-                update_cursor_position.run_if(in_state(GameState::InGame)),
+                update_cursor_position
+                    .in_set(PlayerInputSet)
+                    .run_if(in_state(GameState::InGame))
+                    .run_if(not(is_replaying)),
             );
     }
 }
@@ -48,7 +59,6 @@ fn load_assets(
     mut small_tree_handle: ResMut<SmallTreeTextureAtlas>,
     asset_server: Res<AssetServer>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-    mut next_state: ResMut<NextState<GameState>>,
 ) {
     global_handle.image = Some(asset_server.load(GLOBAL_SPRITE_SHEET_PATH));
 
@@ -70,8 +80,26 @@ fn load_assets(
 
     let small_tree_layout = TextureAtlasLayout::from_grid(UVec2::new(32, 48), 1, 1, None, None);
     small_tree_handle.layout = Some(texture_atlas_layouts.add(small_tree_layout));
+}
 
-    next_state.set(GameState::MainMenu);
+/// Gameplay data has to be in place before a run can start; sprites may pop in later.
+fn finish_loading(
+    weapons: Res<Weapons>,
+    enemy_defs_handle: Res<RonAssetHandle<EnemyDefs>>,
+    enemy_defs: Option<Res<EnemyDefs>>,
+    boss_defs_handle: Res<RonAssetHandle<BossDefs>>,
+    boss_defs: Option<Res<BossDefs>>,
+    asset_server: Res<AssetServer>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if weapons.is_loaded(&asset_server)
+        && enemy_defs_handle.is_loaded(&asset_server)
+        && enemy_defs.is_some()
+        && boss_defs_handle.is_loaded(&asset_server)
+        && boss_defs.is_some()
+    {
+        next_state.set(GameState::MainMenu);
+    }
 }
 
 fn update_cursor_position(
@@ -81,6 +109,7 @@ fn update_cursor_position(
 ) {
     if window_query.is_empty() || camera_query.is_empty() {
         cursor_pos.0 = None;
+        return;
     }
 
     let (camera, camera_transform) = camera_query.single();
@@ -90,30 +119,3 @@ fn update_cursor_position(
         .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
         .map(|ray| ray.origin.truncate());
 }
-
-impl Default for GlobalTextureAtlas {
-    fn default() -> Self {
-        Self {
-            layout: None,
-            image: None,
-        }
-    }
-}
-
-impl Default for BigTreeTextureAtlas {
-    fn default() -> Self {
-        Self {
-            layout: None,
-            image: None,
-        }
-    }
-}
-
-impl Default for SmallTreeTextureAtlas {
-    fn default() -> Self {
-        Self {
-            layout: None,
-            image: None,
-        }
-    }
-}
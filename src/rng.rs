@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::state::GameState;
+
+pub struct RngPlugin {
+    pub seed: Option<u64>,
+}
+
+/// Independent random streams, so drawing from one subsystem never
+/// shifts the sequence seen by another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RngStream {
+    Enemies,
+    Decorations,
+    Trees,
+    Gun,
+}
+
+const NUM_RNG_STREAMS: usize = 4;
+
+/// Seeded game randomness. Every run restarts all streams from the run seed,
+/// which is the `--seed` value when one was given and a fresh one otherwise.
+#[derive(Resource)]
+pub struct GameRng {
+    fixed_seed: Option<u64>,
+    seed: u64,
+    streams: [ChaCha8Rng; NUM_RNG_STREAMS],
+}
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameRng::new(self.seed))
+            .add_systems(OnEnter(GameState::GameInit), reseed_game_rng);
+    }
+}
+
+pub fn reseed_game_rng(mut rng: ResMut<GameRng>) {
+    let seed = rng.fixed_seed.unwrap_or_else(|| rand::thread_rng().gen());
+    rng.reseed(seed);
+    info!("Run seed: {seed}");
+}
+
+impl GameRng {
+    pub fn new(fixed_seed: Option<u64>) -> Self {
+        let seed = fixed_seed.unwrap_or_else(|| rand::thread_rng().gen());
+        Self {
+            fixed_seed,
+            seed,
+            streams: Self::build_streams(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Whether every run uses the seed given on the command line.
+    pub fn is_seed_fixed(&self) -> bool {
+        self.fixed_seed.is_some()
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.streams = Self::build_streams(seed);
+    }
+
+    pub fn stream(&mut self, stream: RngStream) -> &mut ChaCha8Rng {
+        &mut self.streams[stream as usize]
+    }
+
+    fn build_streams(seed: u64) -> [ChaCha8Rng; NUM_RNG_STREAMS] {
+        std::array::from_fn(|index| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            rng.set_stream(index as u64);
+            rng
+        })
+    }
+}
@@ -0,0 +1,92 @@
+use std::marker::PhantomData;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, LoadState};
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+
+use crate::game_config::ConfigError;
+
+/// Gameplay definitions read from a RON file, checked before they are used.
+pub trait RonAsset: Asset + DeserializeOwned {
+    /// Files ending in one of them are loaded as this asset.
+    const EXTENSIONS: &'static [&'static str];
+
+    fn validate(&self) -> Result<(), ConfigError>;
+}
+
+/// Loads any `RonAsset`, rejecting files that don't validate.
+pub struct RonLoader<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for RonLoader<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// Definitions that are also kept as a resource, replaced whenever their file
+/// is loaded or edited.
+#[derive(Resource)]
+pub struct RonAssetHandle<T: Asset>(pub Handle<T>);
+
+impl<T: Asset> RonAssetHandle<T> {
+    pub fn is_loaded(&self, asset_server: &AssetServer) -> bool {
+        is_loaded(asset_server, &self.0)
+    }
+}
+
+/// Whether the asset is ready. Panics if it failed to load, as the game can't
+/// start without its definitions.
+pub fn is_loaded<T: Asset>(asset_server: &AssetServer, handle: &Handle<T>) -> bool {
+    match asset_server.get_load_state(handle) {
+        Some(LoadState::Loaded) => true,
+        Some(LoadState::Failed(err)) => panic!("Failed to load definitions: {err}"),
+        _ => false,
+    }
+}
+
+pub fn apply_ron_asset<T: RonAsset + Resource + Clone>(
+    mut commands: Commands,
+    handle: Res<RonAssetHandle<T>>,
+    assets: Res<Assets<T>>,
+    mut asset_events: EventReader<AssetEvent<T>>,
+) {
+    for event in asset_events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = event else {
+            continue;
+        };
+        if *id != handle.0.id() {
+            continue;
+        }
+
+        if let Some(asset) = assets.get(*id) {
+            commands.insert_resource(asset.clone());
+        }
+    }
+}
+
+impl<T: RonAsset> AssetLoader for RonLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = ConfigError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<T, ConfigError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(ConfigError::Io)?;
+        let asset: T = ron::de::from_bytes(&bytes).map_err(ConfigError::Parse)?;
+        asset.validate()?;
+        Ok(asset)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        T::EXTENSIONS
+    }
+}
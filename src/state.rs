@@ -7,4 +7,22 @@ pub enum GameState {
     MainMenu,
     GameInit,
     InGame,
+    Paused,
+    GameOver,
+}
+
+/// Active while a run is in progress, paused or not. Use `OnExit(InRun)` for
+/// cleanup that must not happen when the game is merely paused.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct InRun;
+
+impl ComputedStates for InRun {
+    type SourceStates = GameState;
+
+    fn compute(sources: GameState) -> Option<Self> {
+        match sources {
+            GameState::InGame | GameState::Paused => Some(InRun),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use bevy::utils::{Duration, HashMap};
+
+use crate::boss::BossKilled;
+use crate::boss_def::BossDefs;
+use crate::enemy::{despawn_dead_enemies, EnemyKilled, EnemyType};
+use crate::enemy_def::EnemyDefs;
+use crate::game_config::GameConfig;
+use crate::state::GameState;
+
+pub struct RunStatsPlugin;
+
+/// Tallies for the current run, shown in the HUD and on the game-over screen.
+/// Reset when a new run starts and kept until the next one.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct RunStats {
+    pub survival_time: Duration,
+    pub score: u64,
+    pub kills: HashMap<EnemyType, u32>,
+    pub bosses_killed: u32,
+    /// Kills chained with less than `score.combo_window_secs` between them.
+    pub combo: u32,
+    /// Time left before the combo runs out.
+    pub combo_time_left: f32,
+    pub shots_fired: u32,
+    pub shots_hit: u32,
+    pub damage_taken: f32,
+}
+
+impl Plugin for RunStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .add_systems(OnEnter(GameState::GameInit), reset_run_stats)
+            .add_systems(
+                Update,
+                (
+                    update_survival_time,
+                    update_combo,
+                    handle_enemy_killed_events.after(despawn_dead_enemies),
+                    handle_boss_killed_events,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+fn update_survival_time(mut stats: ResMut<RunStats>, time: Res<Time>) {
+    stats.survival_time += time.delta();
+}
+
+fn update_combo(mut stats: ResMut<RunStats>, time: Res<Time>) {
+    if stats.combo == 0 {
+        return;
+    }
+
+    stats.combo_time_left -= time.delta_seconds();
+    if stats.combo_time_left <= 0.0 {
+        stats.combo = 0;
+        stats.combo_time_left = 0.0;
+    }
+}
+
+fn handle_enemy_killed_events(
+    config: Res<GameConfig>,
+    enemy_defs: Res<EnemyDefs>,
+    mut stats: ResMut<RunStats>,
+    mut events: EventReader<EnemyKilled>,
+) {
+    for event in events.read() {
+        *stats.kills.entry(event.enemy_type).or_default() += 1;
+        stats.combo += 1;
+        stats.combo_time_left = config.score.combo_window_secs;
+
+        let multiplier = stats.combo_multiplier(&config);
+        let kill_score = enemy_defs.get(event.enemy_type).score;
+        stats.score += (kill_score * multiplier) as u64;
+    }
+}
+
+fn handle_boss_killed_events(
+    config: Res<GameConfig>,
+    boss_defs: Res<BossDefs>,
+    mut stats: ResMut<RunStats>,
+    mut events: EventReader<BossKilled>,
+) {
+    for event in events.read() {
+        stats.bosses_killed += 1;
+        let multiplier = stats.combo_multiplier(&config);
+        if let Some(def) = boss_defs.0.get(event.def) {
+            stats.score += (def.score * multiplier) as u64;
+        }
+    }
+}
+
+impl RunStats {
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+
+    /// Share of fired bullets that hit at least one enemy, from 0 to 1.
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            return 0.0;
+        }
+
+        self.shots_hit as f32 / self.shots_fired as f32
+    }
+
+    /// Starts at x1 and goes up by one every `score.combo_kills_per_step`
+    /// chained kills, up to `score.max_combo_multiplier`.
+    pub fn combo_multiplier(&self, config: &GameConfig) -> u32 {
+        let steps = self.combo.saturating_sub(1) / config.score.combo_kills_per_step;
+        (1 + steps).min(config.score.max_combo_multiplier)
+    }
+}
@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::game_config::{non_negative, positive, ConfigError};
+use crate::projectile::ProjectileModifiers;
+use crate::ron_asset::{self, RonAsset, RonLoader};
+use crate::state::GameState;
+use crate::*;
+
+pub struct WeaponPlugin;
+
+/// A weapon, read from `assets/weapons/<name>.weapon.ron`.
+/// Edits to the file apply to guns using it right away.
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct WeaponDef {
+    pub name: String,
+    /// Shots per second.
+    pub fire_rate: f32,
+    pub projectiles_per_shot: usize,
+    /// Full angle of the cone projectiles are spread over.
+    pub spread_degrees: f32,
+    pub projectile_speed: f32,
+    pub lifetime_secs: f32,
+    pub damage: f32,
+    /// Sprite of the gun while this weapon is equipped.
+    pub gun_sprite_index: usize,
+    /// Sprite of its projectiles.
+    pub sprite_index: usize,
+    pub magazine_size: u32,
+    /// Ammo carried besides the magazine at the start of a run, unlimited if `None`.
+    pub reserve_ammo: Option<u32>,
+    pub reload_secs: f32,
+    #[serde(default)]
+    pub modifiers: ProjectileModifiers,
+}
+
+/// Handles to the `BUILT_IN_WEAPONS` definitions, in the same order.
+#[derive(Resource, Default)]
+pub struct Weapons(Vec<(&'static str, Handle<WeaponDef>)>);
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<WeaponDef>()
+            .register_asset_loader(RonLoader::<WeaponDef>::default())
+            .init_resource::<Weapons>()
+            .add_systems(OnEnter(GameState::Loading), load_weapons);
+    }
+}
+
+fn load_weapons(mut weapons: ResMut<Weapons>, asset_server: Res<AssetServer>) {
+    weapons.0 = BUILT_IN_WEAPONS
+        .iter()
+        .map(|&name| {
+            let path = format!("{WEAPONS_DIR}/{name}.weapon.ron");
+            (name, asset_server.load(path))
+        })
+        .collect();
+}
+
+impl Weapons {
+    pub fn get(&self, name: &str) -> Option<Handle<WeaponDef>> {
+        self.0
+            .iter()
+            .find(|(weapon, _)| *weapon == name)
+            .map(|(_, handle)| handle.clone())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Handle<WeaponDef>> {
+        self.0.iter().map(|(_, handle)| handle)
+    }
+
+    pub fn is_loaded(&self, asset_server: &AssetServer) -> bool {
+        self.iter()
+            .all(|handle| ron_asset::is_loaded(asset_server, handle))
+    }
+}
+
+impl RonAsset for WeaponDef {
+    const EXTENSIONS: &'static [&'static str] = &["weapon.ron"];
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        positive("fire_rate", self.fire_rate)?;
+        non_negative("spread_degrees", self.spread_degrees)?;
+        positive("projectile_speed", self.projectile_speed)?;
+        positive("lifetime_secs", self.lifetime_secs)?;
+        non_negative("damage", self.damage)?;
+        positive("reload_secs", self.reload_secs)?;
+        if self.magazine_size == 0 {
+            return Err(ConfigError::Invalid {
+                key: "magazine_size".into(),
+                reason: "must be greater than 0".into(),
+            });
+        }
+        self.modifiers.validate()
+    }
+}
+
+impl WeaponDef {
+    pub fn fire_interval_secs(&self) -> f32 {
+        1.0 / self.fire_rate
+    }
+}
@@ -4,19 +4,27 @@ use bevy_rapier2d::prelude::*;
 
 use rand::Rng;
 
+use crate::game_config::GameConfig;
+use crate::rng::{reseed_game_rng, GameRng, RngStream};
 use crate::*;
-use crate::{state::GameState, GlobalTextureAtlas};
+use crate::{
+    state::{GameState, InRun},
+    GlobalTextureAtlas,
+};
 
 pub struct WorldPlugin;
 
 impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::GameInit), init_world_decorations)
-            .add_systems(
-                Update,
-                (spawn_world_decorations, spawn_world_trees).run_if(in_state(GameState::InGame)),
-            )
-            .add_systems(OnExit(GameState::InGame), despawn_all_game_entities);
+        app.add_systems(
+            OnEnter(GameState::GameInit),
+            init_world_decorations.after(reseed_game_rng),
+        )
+        .add_systems(
+            Update,
+            (spawn_world_decorations, spawn_world_trees).run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(OnExit(InRun), despawn_all_game_entities);
     }
 }
 #[derive(Component)]
@@ -33,17 +41,16 @@ pub enum DecorationType {
 }
 
 impl DecorationType {
-    fn get_rand_decoration() -> Self {
-        let mut rng = rand::thread_rng();
+    fn get_rand_decoration(rng: &mut impl Rng) -> Self {
         let rand_index = rng.gen_range(0..6);
-        return match rand_index {
+        match rand_index {
             0 => Self::Decoration1,
             1 => Self::Decoration2,
             2 => Self::Decoration3,
             3 => Self::Decoration4,
             4 => Self::Decoration5,
             _ => Self::Decoration6,
-        };
+        }
     }
 
     pub fn get_base_sprite_index(&self) -> usize {
@@ -58,13 +65,19 @@ impl DecorationType {
     }
 }
 
-fn init_world_decorations(mut commands: Commands, handle: Res<GlobalTextureAtlas>) {
-    let mut rng = rand::thread_rng();
-    for _ in 0..NUM_WORLD_DECORATIONS {
-        let x = rng.gen_range(-WORLD_W..WORLD_W);
-        let y = rng.gen_range(-WORLD_H..WORLD_H);
+fn init_world_decorations(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+    handle: Res<GlobalTextureAtlas>,
+) {
+    let rng = rng.stream(RngStream::Decorations);
+    let (world_w, world_h) = (config.world.width, config.world.height);
+    for _ in 0..config.world.num_decorations {
+        let x = rng.gen_range(-world_w..world_w);
+        let y = rng.gen_range(-world_h..world_h);
 
-        let decoration_type = DecorationType::get_rand_decoration();
+        let decoration_type = DecorationType::get_rand_decoration(rng);
 
         commands.spawn((
             SpriteBundle {
@@ -95,21 +108,24 @@ fn despawn_all_game_entities(
 struct Decoration;
 
 #[derive(Component)]
-struct Tree;
+pub struct Tree;
 
 fn spawn_world_decorations(
     mut commands: Commands,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
     handle: Res<GlobalTextureAtlas>,
     camera_query: Query<&Transform, With<Camera>>,
     decoration_query: Query<&Transform, With<Decoration>>,
 ) {
-    let mut rng = rand::thread_rng();
+    let rng = rng.stream(RngStream::Decorations);
     let camera_transform = camera_query.single();
+    let (world_w, world_h) = (config.world.width, config.world.height);
 
     // Count visible decorations
     let mut visible_decorations_count = 0;
     for decoration_transform in decoration_query.iter() {
-        if is_within_camera_view(camera_transform, decoration_transform) {
+        if is_within_camera_view(&config, camera_transform, decoration_transform) {
             visible_decorations_count += 1;
         }
     }
@@ -117,16 +133,19 @@ fn spawn_world_decorations(
     // println!("visible_decorations_count: {:?}", visible_decorations_count);
 
     // Spawn additional decorations if needed
-    let decorations_needed = NUM_WORLD_DECORATIONS.saturating_sub(visible_decorations_count);
+    let decorations_needed = config
+        .world
+        .num_decorations
+        .saturating_sub(visible_decorations_count);
     for _ in 0..decorations_needed {
         let x = rng.gen_range(
-            camera_transform.translation.x - WORLD_W..camera_transform.translation.x + WORLD_W,
+            camera_transform.translation.x - world_w..camera_transform.translation.x + world_w,
         );
         let y = rng.gen_range(
-            camera_transform.translation.y - WORLD_H..camera_transform.translation.y + WORLD_H,
+            camera_transform.translation.y - world_h..camera_transform.translation.y + world_h,
         );
 
-        let decoration_type = DecorationType::get_rand_decoration();
+        let decoration_type = DecorationType::get_rand_decoration(rng);
 
         commands.spawn((
             SpriteBundle {
@@ -140,6 +159,7 @@ fn spawn_world_decorations(
                 index: decoration_type.get_base_sprite_index(),
             },
             Decoration,
+            GameEntity,
         ));
     }
 }
@@ -151,13 +171,16 @@ enum TreeType {
 
 fn spawn_world_trees(
     mut commands: Commands,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
     big_tree_handle: Res<BigTreeTextureAtlas>,
     small_tree_handle: Res<SmallTreeTextureAtlas>,
     camera_query: Query<&Transform, With<Camera>>,
     tree_query: Query<&Transform, With<Tree>>,
 ) {
-    let mut rng = rand::thread_rng();
+    let rng = rng.stream(RngStream::Trees);
     let camera_transform = camera_query.single();
+    let (world_w, world_h) = (config.world.width, config.world.height);
 
     // Count visible decorations
     let mut visible_trees_count = 0;
@@ -168,19 +191,19 @@ fn spawn_world_trees(
         TreeType::Small
     };
     for trees_transform in tree_query.iter() {
-        if is_within_camera_view(camera_transform, trees_transform) {
+        if is_within_camera_view(&config, camera_transform, trees_transform) {
             visible_trees_count += 1;
         }
     }
 
     // Spawn additional trees if needed
-    let trees_needed = NUM_WORLD_TREES.saturating_sub(visible_trees_count);
+    let trees_needed = config.world.num_trees.saturating_sub(visible_trees_count);
     for _ in 0..trees_needed {
         let x = rng.gen_range(
-            camera_transform.translation.x - WORLD_W..camera_transform.translation.x + WORLD_W,
+            camera_transform.translation.x - world_w..camera_transform.translation.x + world_w,
         );
         let y = rng.gen_range(
-            camera_transform.translation.y - WORLD_H..camera_transform.translation.y + WORLD_H,
+            camera_transform.translation.y - world_h..camera_transform.translation.y + world_h,
         );
 
         match tree_type {
@@ -197,6 +220,7 @@ fn spawn_world_trees(
                         index: 0,
                     },
                     Tree,
+                    GameEntity,
                     RigidBody::Fixed,
                     Collider::cuboid(24.0, 32.0),
                 ));
@@ -214,6 +238,7 @@ fn spawn_world_trees(
                         index: 0,
                     },
                     Tree,
+                    GameEntity,
                     RigidBody::Fixed,
                     Collider::cuboid(16.0, 24.0),
                 ));
@@ -222,8 +247,12 @@ fn spawn_world_trees(
     }
 }
 
-fn is_within_camera_view(camera_transform: &Transform, entity_transform: &Transform) -> bool {
-    let view_distance = Vec3::new(WORLD_W / 2.0, WORLD_H / 2.0, 0.0);
+fn is_within_camera_view(
+    config: &GameConfig,
+    camera_transform: &Transform,
+    entity_transform: &Transform,
+) -> bool {
+    let view_distance = Vec3::new(config.world.width / 2.0, config.world.height / 2.0, 0.0);
     let offset = entity_transform.translation - camera_transform.translation;
 
     offset.x.abs() <= view_distance.x
@@ -0,0 +1,66 @@
+mod common;
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use common::{gun_angle, spawn_enemy};
+use infinite_shooter_game::aim::{assist_direction, AimMode, AutoAimTarget};
+
+fn aim_at(mode: AimMode) -> f32 {
+    let mut app = common::frozen()
+        .configure(|config| config.aim.mode = mode)
+        .build();
+    let player = app.player_transform().unwrap().translation.truncate();
+    // Straight up and close but healthy, to the right and far but weak.
+    spawn_enemy(&mut app, player + Vec2::Y * 150.0, 500.0);
+    spawn_enemy(&mut app, player + Vec2::X * 400.0, 50.0);
+    app.step_secs(0.5);
+    gun_angle(&mut app)
+}
+
+#[test]
+fn auto_aim_targets_the_nearest_enemy() {
+    let angle = aim_at(AimMode::Auto(AutoAimTarget::Nearest));
+    assert!((angle - FRAC_PI_2).abs() < 0.05, "{angle}");
+}
+
+#[test]
+fn auto_aim_targets_the_weakest_enemy() {
+    let angle = aim_at(AimMode::Auto(AutoAimTarget::LowestHealth));
+    assert!(angle.abs() < 0.05, "{angle}");
+}
+
+#[test]
+fn auto_aim_targets_the_biggest_threat() {
+    // 500 health at 150 units outweighs 50 health at 400.
+    let angle = aim_at(AimMode::Auto(AutoAimTarget::HighestThreat));
+    assert!((angle - FRAC_PI_2).abs() < 0.05, "{angle}");
+}
+
+#[test]
+fn auto_aim_ignores_enemies_out_of_range() {
+    let mut app = common::empty()
+        .configure(|config| {
+            config.aim.mode = AimMode::Auto(AutoAimTarget::Nearest);
+            config.aim.range = 100.0;
+        })
+        .build();
+    let player = app.player_transform().unwrap().translation.truncate();
+    spawn_enemy(&mut app, player + Vec2::Y * 150.0, 500.0);
+    app.step_secs(0.5);
+
+    // Without a cursor the gun keeps its default heading.
+    let angle = gun_angle(&mut app);
+    assert!((angle - FRAC_PI_2).abs() > 0.5, "{angle}");
+}
+
+#[test]
+fn aim_assist_turns_part_of_the_way_to_the_target() {
+    let aim = Vec2::X * 200.0;
+    let target = Vec2::from_angle(0.4) * 300.0;
+
+    let nudged = assist_direction(aim, target, 0.5);
+    assert!((nudged.to_angle() - 0.2).abs() < 1e-4);
+    assert!((nudged.length() - 200.0).abs() < 1e-3);
+    assert_eq!(assist_direction(aim, target, 0.0), aim);
+}
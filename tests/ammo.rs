@@ -0,0 +1,78 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{active_weapon, set_fire, tap_key};
+use infinite_shooter_game::gun::{Gun, WeaponInventory};
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::player::Player;
+use infinite_shooter_game::stats::RunStats;
+
+/// Magazine and reserve of the active weapon.
+fn ammo(app: &mut HeadlessApp) -> (u32, Option<u32>) {
+    let world = app.world_mut();
+    let inventory = world
+        .query_filtered::<&WeaponInventory, With<Player>>()
+        .single(world);
+    let slot = inventory.active_slot();
+    (slot.magazine, slot.reserve)
+}
+
+fn is_reloading(app: &mut HeadlessApp) -> bool {
+    let world = app.world_mut();
+    world.query::<&Gun>().single(world).reload.is_some()
+}
+
+#[test]
+fn empty_magazine_reloads_from_the_reserve() {
+    let mut app = common::empty()
+        .configure(|config| config.gun.weapons = vec!["smg".to_string()])
+        .build();
+    let smg = active_weapon(&mut app);
+    let reserve = smg.reserve_ammo.unwrap();
+    assert_eq!(ammo(&mut app), (smg.magazine_size, Some(reserve)));
+
+    set_fire(&mut app, true);
+    while !is_reloading(&mut app) {
+        app.step(1);
+    }
+    assert_eq!(ammo(&mut app), (0, Some(reserve)));
+
+    // Firing is blocked while reloading.
+    let fired = app.world().resource::<RunStats>().shots_fired;
+    assert_eq!(fired, smg.magazine_size);
+    app.step_secs(smg.reload_secs / 2.0);
+    assert_eq!(app.world().resource::<RunStats>().shots_fired, fired);
+    assert!(is_reloading(&mut app));
+
+    set_fire(&mut app, false);
+    app.step_secs(smg.reload_secs / 2.0 + 0.1);
+    assert!(!is_reloading(&mut app));
+    assert_eq!(
+        ammo(&mut app),
+        (smg.magazine_size, Some(reserve - smg.magazine_size))
+    );
+}
+
+#[test]
+fn r_reloads_a_partly_empty_magazine() {
+    let mut app = common::empty()
+        .configure(|config| config.gun.weapons = vec!["pistol".to_string()])
+        .build();
+    let pistol = active_weapon(&mut app);
+    assert_eq!(pistol.reserve_ammo, None);
+
+    set_fire(&mut app, true);
+    while ammo(&mut app).0 == pistol.magazine_size {
+        app.step(1);
+    }
+    set_fire(&mut app, false);
+    app.step(1);
+    assert!(!is_reloading(&mut app));
+
+    tap_key(&mut app, KeyCode::KeyR);
+    assert!(is_reloading(&mut app));
+
+    app.step_secs(pistol.reload_secs);
+    assert!(!is_reloading(&mut app));
+    assert_eq!(ammo(&mut app), (pistol.magazine_size, None));
+}
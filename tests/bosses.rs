@@ -0,0 +1,169 @@
+mod common;
+
+use bevy::prelude::*;
+use common::set_player_health;
+use infinite_shooter_game::boss::Boss;
+use infinite_shooter_game::boss_def::BossDefs;
+use infinite_shooter_game::enemy::Enemy;
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::pickup::Reward;
+use infinite_shooter_game::player::{Health, Player};
+use infinite_shooter_game::ranged::EnemyProjectile;
+use infinite_shooter_game::ron_asset::RonAsset;
+use infinite_shooter_game::stats::RunStats;
+use infinite_shooter_game::SPRITE_SCALE_FACTOR;
+
+const BOSSES: &str = "[(
+    name: \"Test Boss\",
+    sprite: Skin,
+    scale: 2.0,
+    health: 1000.0,
+    mass: 100.0,
+    collider_radius: 9.0,
+    contact_damage: 10.0,
+    contact_interval_secs: 0.5,
+    score: 100,
+    trigger: AfterSecs(0.5),
+    phases: [
+        (health_fraction: 1.0, speed: 0.0, movement: Chase),
+        (
+            health_fraction: 0.5,
+            speed: 0.0,
+            movement: Chase,
+            attack: Some((
+                projectiles: 8,
+                spread_degrees: 360.0,
+                interval_secs: 0.5,
+                telegraph_secs: 0.2,
+                projectile_speed: 200.0,
+                projectile_damage: 1.0,
+                projectile_lifetime_secs: 5.0,
+            )),
+        ),
+    ],
+    rewards: [Health(20.0), Score(50)],
+)]";
+
+fn app() -> HeadlessApp {
+    let mut app = common::empty().build();
+    let defs: BossDefs = ron::from_str(BOSSES).unwrap();
+    defs.validate().unwrap();
+    app.world_mut().insert_resource(defs);
+    app
+}
+
+fn wait_for_boss(app: &mut HeadlessApp) -> Entity {
+    let mut frames = 0;
+    loop {
+        let world = app.world_mut();
+        if let Some(boss) = world
+            .query_filtered::<Entity, With<Boss>>()
+            .iter(world)
+            .next()
+        {
+            return boss;
+        }
+        app.step(1);
+        frames += 1;
+        assert!(frames < 600, "the boss never showed up");
+    }
+}
+
+fn set_boss_health(app: &mut HeadlessApp, boss: Entity, health: f32) {
+    app.world_mut().get_mut::<Enemy>(boss).unwrap().health = health;
+}
+
+#[test]
+fn bosses_spawn_once_their_trigger_is_met() {
+    let mut app = app();
+    app.step_secs(0.4);
+    assert_eq!(app.count::<Boss>(), 0);
+
+    let boss = wait_for_boss(&mut app);
+    let transform = app.world().get::<Transform>(boss).unwrap();
+    assert_eq!(transform.scale.x, SPRITE_SCALE_FACTOR * 2.0);
+    assert_eq!(app.world().get::<Boss>(boss).unwrap().max_health, 1000.0);
+
+    set_boss_health(&mut app, boss, 0.0);
+    app.step_secs(1.0);
+    assert_eq!(app.count::<Boss>(), 0);
+    assert_eq!(app.world().resource::<RunStats>().bosses_killed, 1);
+    // Bosses aren't regular kills.
+    assert_eq!(app.world().resource::<RunStats>().total_kills(), 0);
+}
+
+#[test]
+fn bosses_change_phase_and_attack_at_health_thresholds() {
+    let mut app = app();
+    let boss = wait_for_boss(&mut app);
+    app.step_secs(1.0);
+    assert_eq!(app.world().get::<Boss>(boss).unwrap().phase, 0);
+    assert_eq!(app.count::<EnemyProjectile>(), 0);
+
+    set_boss_health(&mut app, boss, 400.0);
+    app.step(1);
+    assert_eq!(app.world().get::<Boss>(boss).unwrap().phase, 1);
+
+    while !app.world().get::<Boss>(boss).unwrap().is_telegraphing() {
+        app.step(1);
+    }
+    assert_eq!(app.count::<EnemyProjectile>(), 0);
+    while app.count::<EnemyProjectile>() == 0 {
+        app.step(1);
+    }
+    assert_eq!(app.count::<EnemyProjectile>(), 8);
+}
+
+#[test]
+fn touching_a_boss_hurts_once_per_contact_interval() {
+    let mut app = app();
+    let boss = wait_for_boss(&mut app);
+    let player_pos = app.player_transform().unwrap().translation;
+    // Just touching: player radius 12 and boss radius 9, both scaled.
+    let touching = (12.0 + 9.0 * 2.0) * SPRITE_SCALE_FACTOR;
+    app.world_mut()
+        .get_mut::<Transform>(boss)
+        .unwrap()
+        .translation = player_pos + Vec3::X * touching;
+    app.step_secs(0.9);
+
+    let damage_taken = app.world().resource::<RunStats>().damage_taken;
+    assert_eq!(damage_taken, 20.0);
+}
+
+#[test]
+fn dead_bosses_drop_their_rewards() {
+    let mut app = app();
+    let boss = wait_for_boss(&mut app);
+    let boss_pos = app.world().get::<Transform>(boss).unwrap().translation;
+    set_boss_health(&mut app, boss, 0.0);
+    app.step(1);
+    assert_eq!(app.world().resource::<RunStats>().score, 100);
+
+    let world = app.world_mut();
+    let mut pickups = world.query::<(&Transform, &Reward)>();
+    let rewards: Vec<_> = pickups.iter(world).collect();
+    assert_eq!(rewards.len(), 2);
+    for (transform, _) in &rewards {
+        assert!(transform.translation.distance(boss_pos) < 100.0);
+    }
+
+    let player_pos = app.player_transform().unwrap().translation;
+    set_player_health(&mut app, 50.0);
+    let world = app.world_mut();
+    for mut transform in world
+        .query_filtered::<&mut Transform, With<Reward>>()
+        .iter_mut(world)
+    {
+        transform.translation = player_pos;
+    }
+    app.step(1);
+
+    assert_eq!(app.count::<Reward>(), 0);
+    assert_eq!(app.world().resource::<RunStats>().score, 150);
+    let world = app.world_mut();
+    let health = world
+        .query_filtered::<&Health, With<Player>>()
+        .single(world);
+    assert_eq!(health.0, 70.0);
+}
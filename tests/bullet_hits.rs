@@ -0,0 +1,55 @@
+mod common;
+
+use bevy::math::vec2;
+use bevy::prelude::*;
+use common::{enemy_health, spawn_enemy};
+use infinite_shooter_game::gun::Pierce;
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::projectile::ProjectileModifiers;
+use infinite_shooter_game::stats::RunStats;
+
+const DAMAGE: f32 = 15.0;
+
+/// A bullet standing still at `x`.
+fn spawn_bullet(app: &mut HeadlessApp, x: f32, pierce: u32) -> Entity {
+    let modifiers = ProjectileModifiers {
+        pierce,
+        ..default()
+    };
+    common::spawn_bullet(app, vec2(x, 0.0), Vec2::ZERO, DAMAGE, modifiers)
+}
+
+#[test]
+fn bullets_hit_the_enemy_they_touch_and_are_used_up() {
+    let mut app = common::frozen().build();
+    let near = spawn_enemy(&mut app, vec2(300.0, 0.0), 100.0);
+    let far = spawn_enemy(&mut app, vec2(600.0, 0.0), 100.0);
+    // Scaled collider radius is 24, bullet radius 4.
+    app.step_secs(0.2);
+    let hitting = spawn_bullet(&mut app, 327.0, 0);
+    let missing = spawn_bullet(&mut app, 630.0, 0);
+    app.step_secs(0.5);
+
+    assert_eq!(enemy_health(&app, near), 100.0 - DAMAGE);
+    assert_eq!(enemy_health(&app, far), 100.0);
+    assert!(app.world().get_entity(hitting).is_none());
+    assert!(app.world().get_entity(missing).is_some());
+    assert_eq!(app.world().resource::<RunStats>().shots_hit, 1);
+}
+
+#[test]
+fn piercing_bullets_hit_each_enemy_once() {
+    let mut app = common::frozen().build();
+    let first = spawn_enemy(&mut app, vec2(300.0, 0.0), 100.0);
+    let second = spawn_enemy(&mut app, vec2(340.0, 0.0), 100.0);
+    app.step_secs(0.2);
+    let bullet = spawn_bullet(&mut app, 320.0, 2);
+    app.step_secs(0.5);
+
+    assert_eq!(enemy_health(&app, first), 100.0 - DAMAGE);
+    assert_eq!(enemy_health(&app, second), 100.0 - DAMAGE);
+    let pierce = app.world().get::<Pierce>(bullet).unwrap();
+    assert_eq!(pierce.remaining, 0);
+    assert_eq!(pierce.hit.len(), 2);
+    assert_eq!(app.world().resource::<RunStats>().shots_hit, 1);
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use bevy::prelude::*;
+use common::set_fire;
+use infinite_shooter_game::gun::Bullet;
+use infinite_shooter_game::headless::HeadlessApp;
+
+/// Pistol bullets live 0.8s of game time.
+fn app(time_scale: f32) -> HeadlessApp {
+    let mut app = common::empty()
+        .configure(|config| config.gun.weapons = vec!["pistol".to_string()])
+        .build();
+    app.world_mut()
+        .resource_mut::<Time<Virtual>>()
+        .set_relative_speed(time_scale);
+    app
+}
+
+/// Fires a single shot and returns the real seconds its bullet lived.
+fn bullet_lifetime_secs(app: &mut HeadlessApp) -> f32 {
+    set_fire(app, true);
+    while app.count::<Bullet>() == 0 {
+        app.step(1);
+    }
+    set_fire(app, false);
+
+    let fired = app.world().resource::<Time<Real>>().elapsed_seconds();
+    while app.count::<Bullet>() > 0 {
+        app.step(1);
+    }
+    app.world().resource::<Time<Real>>().elapsed_seconds() - fired
+}
+
+#[test]
+fn bullets_age_in_game_time() {
+    let lifetime = bullet_lifetime_secs(&mut app(1.0));
+    assert!((0.75..0.85).contains(&lifetime), "{lifetime}");
+}
+
+#[test]
+fn slow_motion_stretches_bullet_lifetime() {
+    let lifetime = bullet_lifetime_secs(&mut app(0.5));
+    assert!((1.55..1.7).contains(&lifetime), "{lifetime}");
+}
+
+#[test]
+fn fast_forward_shortens_bullet_lifetime() {
+    let lifetime = bullet_lifetime_secs(&mut app(2.0));
+    assert!((0.35..0.45).contains(&lifetime), "{lifetime}");
+}
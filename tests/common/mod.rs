@@ -0,0 +1,126 @@
+//! Helpers shared by the integration tests. Not every test uses all of them.
+#![allow(dead_code)]
+
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use infinite_shooter_game::enemy::Enemy;
+use infinite_shooter_game::gun::{Bullet, BulletVelocity, Gun};
+use infinite_shooter_game::headless::{HeadlessApp, HeadlessAppBuilder};
+use infinite_shooter_game::player::{Health, Player};
+use infinite_shooter_game::projectile::ProjectileModifiers;
+use infinite_shooter_game::weapon::WeaponDef;
+use infinite_shooter_game::SPRITE_SCALE_FACTOR;
+
+/// Seeded, and without spawned enemies, trees or decorations, so only what
+/// the test places is in the world.
+pub fn empty() -> HeadlessAppBuilder {
+    HeadlessApp::builder().seed(1).configure(|config| {
+        config.world.num_decorations = 0;
+        config.world.num_trees = 0;
+        config.enemy.max_num_enemies = 0;
+    })
+}
+
+/// Like `empty`, with enemies standing still.
+pub fn frozen() -> HeadlessAppBuilder {
+    empty().configure(|config| config.enemy.speed_scale = 0.0)
+}
+
+/// An enemy of regular size without an `EnemyType`, so it neither moves nor
+/// hurts the player unless the test adds one.
+pub fn spawn_enemy(app: &mut HeadlessApp, position: Vec2, health: f32) -> Entity {
+    app.world_mut()
+        .spawn((
+            TransformBundle::from_transform(
+                Transform::from_translation(position.extend(1.0))
+                    .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
+            ),
+            Enemy {
+                health,
+                last_hit_by: None,
+            },
+            Collider::ball(8.0),
+            Sensor,
+        ))
+        .id()
+}
+
+pub fn enemy_health(app: &HeadlessApp, enemy: Entity) -> f32 {
+    app.world().get::<Enemy>(enemy).unwrap().health
+}
+
+pub fn spawn_bullet(
+    app: &mut HeadlessApp,
+    position: Vec2,
+    velocity: Vec2,
+    damage: f32,
+    modifiers: ProjectileModifiers,
+) -> Entity {
+    let bullet = app
+        .world_mut()
+        .spawn((
+            Transform::from_translation(position.extend(1.0)),
+            Bullet { damage },
+            BulletVelocity(velocity.extend(0.0)),
+        ))
+        .id();
+    let mut commands = app.world_mut().commands();
+    modifiers.insert_into(&mut commands.entity(bullet));
+    app.world_mut().flush();
+    bullet
+}
+
+pub fn active_weapon(app: &mut HeadlessApp) -> WeaponDef {
+    let world = app.world_mut();
+    let gun = world.query::<&Gun>().single(world);
+    world
+        .resource::<Assets<WeaponDef>>()
+        .get(&gun.weapon)
+        .unwrap()
+        .clone()
+}
+
+/// Sends a key event the way a window does, so `just_pressed` works.
+pub fn send_key(app: &mut HeadlessApp, key_code: KeyCode, state: ButtonState) {
+    app.world_mut().send_event(KeyboardInput {
+        key_code,
+        logical_key: Key::Unidentified(NativeKey::Unidentified),
+        state,
+        window: Entity::PLACEHOLDER,
+    });
+}
+
+/// Presses and releases `key_code`, stepping a frame after each.
+pub fn tap_key(app: &mut HeadlessApp, key_code: KeyCode) {
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        send_key(app, key_code, state);
+        app.step(1);
+    }
+}
+
+/// Holds or lets go of the fire button.
+pub fn set_fire(app: &mut HeadlessApp, fire: bool) {
+    let mut mouse = app.world_mut().resource_mut::<ButtonInput<MouseButton>>();
+    if fire {
+        mouse.press(MouseButton::Left);
+    } else {
+        mouse.release(MouseButton::Left);
+    }
+}
+
+/// Direction the gun points in, from the player.
+pub fn gun_angle(app: &mut HeadlessApp) -> f32 {
+    let world = app.world_mut();
+    let (gun_transform, _) = world.query::<(&Transform, &Gun)>().single(world);
+    gun_transform.local_x().truncate().to_angle()
+}
+
+pub fn set_player_health(app: &mut HeadlessApp, health: f32) {
+    let world = app.world_mut();
+    world
+        .query_filtered::<&mut Health, With<Player>>()
+        .single_mut(world)
+        .0 = health;
+}
@@ -0,0 +1,199 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy::utils::Duration;
+use common::{enemy_health, spawn_bullet, spawn_enemy};
+use infinite_shooter_game::elite::{
+    elite_chance, Armor, DeathExplosion, Elite, EliteAffix, Haste, LifeSteal, Regeneration,
+};
+use infinite_shooter_game::enemy::{Enemy, EnemyType};
+use infinite_shooter_game::game_config::{EliteConfig, GameConfig};
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::pickup::Reward;
+use infinite_shooter_game::projectile::ProjectileModifiers;
+use infinite_shooter_game::stats::RunStats;
+use infinite_shooter_game::ARMOR_MIN_DAMAGE_FRACTION;
+
+#[test]
+fn elite_chance_rises_with_run_time_up_to_the_cap() {
+    let config = EliteConfig {
+        base_chance: 0.05,
+        chance_per_minute: 0.1,
+        max_chance: 0.3,
+        ..default()
+    };
+
+    assert_eq!(elite_chance(&config, Duration::ZERO), 0.05);
+    let after_one_minute = elite_chance(&config, Duration::from_secs(60));
+    assert!((after_one_minute - 0.15).abs() < 1e-6);
+    assert_eq!(elite_chance(&config, Duration::from_secs(600)), 0.3);
+}
+
+#[test]
+fn elites_are_tinted_and_get_their_affixes() {
+    let mut config = GameConfig::default();
+    config.world.num_trees = 0;
+    config.enemy.max_num_enemies = 20;
+    config.elite.base_chance = 1.0;
+    config.elite.max_chance = 1.0;
+    config.elite.affixes = EliteAffix::ALL.len();
+    let mut app = HeadlessApp::builder().config(config).seed(1).build();
+    app.step_secs(1.5);
+
+    let world = app.world_mut();
+    let mut elites = world.query_filtered::<(&Elite, &Sprite), (
+        With<Armor>,
+        With<Haste>,
+        With<Regeneration>,
+        With<LifeSteal>,
+        With<DeathExplosion>,
+    )>();
+    let elites: Vec<_> = elites.iter(world).collect();
+    assert_eq!(elites.len(), 20);
+    for (elite, sprite) in elites {
+        assert_eq!(elite.affixes.len(), EliteAffix::ALL.len());
+        assert_ne!(sprite.color, Color::WHITE);
+        assert_eq!(sprite.color, elite.color());
+    }
+}
+
+#[test]
+fn armor_takes_a_flat_amount_off_every_hit() {
+    let mut app = common::frozen().build();
+    let enemy = spawn_enemy(&mut app, Vec2::new(300.0, 0.0), 100.0);
+    app.world_mut().entity_mut(enemy).insert(Armor(15.0));
+    app.step_secs(0.2);
+    for damage in [40.0, 10.0] {
+        let modifiers = ProjectileModifiers::default();
+        spawn_bullet(
+            &mut app,
+            Vec2::new(327.0, 0.0),
+            Vec2::ZERO,
+            damage,
+            modifiers,
+        );
+        app.step_secs(0.2);
+    }
+
+    let expected = 100.0 - 25.0 - 10.0 * ARMOR_MIN_DAMAGE_FRACTION;
+    assert!((enemy_health(&app, enemy) - expected).abs() < 1e-4);
+}
+
+#[test]
+fn hasted_elites_move_faster() {
+    let mut app = common::empty().build();
+    let start = Vec3::new(2000.0, 0.0, 1.0);
+    let normal = spawn_enemy(&mut app, start.truncate(), 100.0);
+    let hasted = spawn_enemy(&mut app, start.truncate().with_y(500.0), 100.0);
+    app.world_mut().entity_mut(normal).insert(EnemyType::Green);
+    app.world_mut()
+        .entity_mut(hasted)
+        .insert((EnemyType::Green, Haste(2.0)));
+    app.step_secs(1.0);
+
+    let travelled = |enemy: Entity, from: Vec3| {
+        app.world()
+            .get::<Transform>(enemy)
+            .unwrap()
+            .translation
+            .distance(from)
+    };
+    let ratio = travelled(hasted, start.with_y(500.0)) / travelled(normal, start);
+    assert!((ratio - 2.0).abs() < 0.01, "{ratio}");
+}
+
+#[test]
+fn regeneration_heals_up_to_the_maximum() {
+    let mut app = common::frozen().build();
+    let enemy = spawn_enemy(&mut app, Vec2::new(1000.0, 0.0), 50.0);
+    app.world_mut().entity_mut(enemy).insert(Regeneration {
+        per_sec: 4.0,
+        max_health: 60.0,
+    });
+
+    app.step_secs(1.0);
+    assert!((enemy_health(&app, enemy) - 54.0).abs() < 0.1);
+    app.step_secs(5.0);
+    assert_eq!(enemy_health(&app, enemy), 60.0);
+}
+
+#[test]
+fn vampiric_elites_heal_by_hurting_the_player() {
+    let mut app = common::frozen().build();
+    let player_pos = app.player_transform().unwrap().translation;
+    let enemy = spawn_enemy(&mut app, (player_pos + Vec3::X * 30.0).truncate(), 10.0);
+    app.world_mut().entity_mut(enemy).insert((
+        EnemyType::Green,
+        LifeSteal {
+            per_damage: 2.0,
+            max_health: 100.0,
+        },
+    ));
+    app.step_secs(0.5);
+    // Break contact, then wait for the enemy tree to refresh and the last
+    // damage events to reach the stats.
+    app.world_mut()
+        .get_mut::<Transform>(enemy)
+        .unwrap()
+        .translation = player_pos + Vec3::X * 1000.0;
+    app.step_secs(0.5);
+
+    let damage_taken = app.world().resource::<RunStats>().damage_taken;
+    assert!(damage_taken > 0.0);
+    let expected = (10.0 + damage_taken * 2.0).min(100.0);
+    assert!((enemy_health(&app, enemy) - expected).abs() < 1e-3);
+}
+
+#[test]
+fn volatile_elites_explode_and_drop_rewards_when_they_die() {
+    let mut app = common::frozen()
+        .configure(|config| config.enemy.damage_scale = 2.0)
+        .build();
+    let rewards = app.world().resource::<GameConfig>().elite.rewards.len();
+    let player_pos = app.player_transform().unwrap().translation;
+    for distance in [100.0, 300.0] {
+        let enemy = spawn_enemy(&mut app, (player_pos + Vec3::Y * distance).truncate(), 0.0);
+        app.world_mut().entity_mut(enemy).insert((
+            EnemyType::Green,
+            Elite {
+                affixes: vec![EliteAffix::Volatile],
+            },
+            DeathExplosion {
+                radius: 150.0,
+                damage: 7.0,
+            },
+        ));
+    }
+    app.step(2);
+
+    assert_eq!(app.count::<Enemy>(), 0);
+    assert_eq!(app.world().resource::<RunStats>().damage_taken, 14.0);
+    assert_eq!(app.count::<Reward>(), rewards * 2);
+}
+
+#[test]
+fn elites_killed_by_bullets_explode_and_drop_rewards() {
+    let mut app = common::frozen().build();
+    let rewards = app.world().resource::<GameConfig>().elite.rewards.len();
+    let player_pos = app.player_transform().unwrap().translation.truncate();
+    let position = player_pos + Vec2::Y * 100.0;
+    let enemy = spawn_enemy(&mut app, position, 10.0);
+    app.world_mut().entity_mut(enemy).insert((
+        EnemyType::Green,
+        Elite {
+            affixes: vec![EliteAffix::Volatile],
+        },
+        DeathExplosion {
+            radius: 150.0,
+            damage: 7.0,
+        },
+    ));
+    app.step_secs(0.2);
+    let modifiers = ProjectileModifiers::default();
+    spawn_bullet(&mut app, position, Vec2::ZERO, 50.0, modifiers);
+    app.step(2);
+
+    assert_eq!(app.count::<Enemy>(), 0);
+    assert_eq!(app.world().resource::<RunStats>().damage_taken, 7.0);
+    assert_eq!(app.count::<Reward>(), rewards);
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use bevy::prelude::*;
+use infinite_shooter_game::charger::ChargerState;
+use infinite_shooter_game::enemy::{Enemy, EnemyType};
+use infinite_shooter_game::enemy_def::{ChargerDef, EnemyBehaviour, EnemyDefs, SplitDef};
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::ron_asset::RonAsset;
+use infinite_shooter_game::{PACK_SPREAD, SPRITE_SCALE_FACTOR};
+
+const SWARM_DEFS: &str = "{
+    Green: (health: 10.0, speed: 0.0, damage: 1.0, mass: 10.0, collider_radius: 8.0, score: 1, spawn_weight: 1, pack_size: 5),
+    Red: (health: 100.0, speed: 0.0, damage: 1.0, mass: 10.0, collider_radius: 8.0, score: 1, spawn_weight: 0),
+    Skin: (health: 100.0, speed: 0.0, damage: 1.0, mass: 10.0, collider_radius: 8.0, score: 1, spawn_weight: 0),
+    White: (health: 100.0, speed: 0.0, damage: 1.0, mass: 10.0, collider_radius: 8.0, score: 1, spawn_weight: 0),
+}";
+
+/// Room for `max_num_enemies`, but none spawn on their own.
+fn splitter_app(max_num_enemies: usize) -> HeadlessApp {
+    common::empty()
+        .configure(|config| {
+            config.enemy.max_num_enemies = max_num_enemies;
+            config.enemy.spawn_rate_per_second = 0;
+        })
+        .build()
+}
+
+fn skin_split(app: &HeadlessApp) -> SplitDef {
+    app.world()
+        .resource::<EnemyDefs>()
+        .get(EnemyType::Skin)
+        .split
+        .clone()
+        .expect("Skin enemies should split")
+}
+
+fn spawn_dead_skin(app: &mut HeadlessApp, x: f32) {
+    app.world_mut().spawn((
+        TransformBundle::from_transform(Transform::from_xyz(x, 0.0, 1.0)),
+        Enemy {
+            health: 0.0,
+            last_hit_by: None,
+        },
+        EnemyType::Skin,
+    ));
+}
+
+fn charger_def(app: &HeadlessApp) -> ChargerDef {
+    let defs = app.world().resource::<EnemyDefs>();
+    let EnemyBehaviour::Charger(charger) = &defs.get(EnemyType::Red).behaviour else {
+        panic!("Red enemies should be chargers");
+    };
+    charger.clone()
+}
+
+fn enemy_pos(app: &HeadlessApp, enemy: Entity) -> Vec2 {
+    app.world()
+        .get::<Transform>(enemy)
+        .unwrap()
+        .translation
+        .truncate()
+}
+
+#[test]
+fn chargers_wind_up_then_dash_in_a_straight_line() {
+    let mut app = common::empty().build();
+    let charger = charger_def(&app);
+    let player_pos = app.player_transform().unwrap().translation.truncate();
+    let start = player_pos + Vec2::new(-120.0, 160.0);
+    let enemy = app
+        .world_mut()
+        .spawn((
+            TransformBundle::from_transform(Transform::from_translation(start.extend(1.0))),
+            Sprite::default(),
+            Enemy {
+                health: 100.0,
+                last_hit_by: None,
+            },
+            EnemyType::Red,
+            ChargerState::new(&charger),
+        ))
+        .id();
+
+    app.step(1);
+    assert!(app
+        .world()
+        .get::<ChargerState>(enemy)
+        .unwrap()
+        .is_charging());
+    assert_ne!(
+        app.world().get::<Sprite>(enemy).unwrap().color,
+        Color::WHITE
+    );
+    let windup_pos = enemy_pos(&app, enemy);
+    app.step_secs(charger.windup_secs * 0.8);
+    assert_eq!(enemy_pos(&app, enemy), windup_pos);
+
+    while !app.world().get::<ChargerState>(enemy).unwrap().is_dashing() {
+        app.step(1);
+    }
+    let dash_start = enemy_pos(&app, enemy);
+    app.step_secs(charger.dash_secs * 0.5);
+    let travelled = enemy_pos(&app, enemy) - dash_start;
+    let toward_player = (player_pos - windup_pos).normalize();
+    assert!(travelled.normalize().dot(toward_player) > 0.999);
+    let expected = charger.dash_speed * charger.dash_secs * 0.5;
+    assert!(
+        (travelled.length() - expected).abs() < expected * 0.05,
+        "dashed {} instead of {expected}",
+        travelled.length()
+    );
+}
+
+#[test]
+fn splitters_leave_smaller_enemies_behind() {
+    let mut app = splitter_app(100);
+    let split = skin_split(&app);
+    spawn_dead_skin(&mut app, 500.0);
+    app.step(1);
+
+    let world = app.world_mut();
+    let mut enemies = world.query::<(&EnemyType, &Transform)>();
+    let offspring: Vec<_> = enemies.iter(world).collect();
+    assert_eq!(offspring.len(), split.count as usize);
+    for (enemy_type, transform) in offspring {
+        assert_eq!(*enemy_type, split.into);
+        assert_eq!(transform.scale.x, SPRITE_SCALE_FACTOR * split.scale);
+    }
+}
+
+#[test]
+fn split_offspring_only_fill_the_room_under_the_enemy_cap() {
+    let mut app = splitter_app(4);
+    assert_eq!(skin_split(&app).count, 3);
+    common::spawn_enemy(&mut app, Vec2::new(-500.0, 0.0), 100.0);
+    spawn_dead_skin(&mut app, 500.0);
+    spawn_dead_skin(&mut app, 800.0);
+    app.step(1);
+
+    // The first splitter has room for 2 of its 3 offspring, the second for 1.
+    assert_eq!(app.count::<Enemy>(), 4);
+}
+
+#[test]
+fn swarms_spawn_in_packs() {
+    let mut app = common::empty()
+        .configure(|config| config.enemy.max_num_enemies = 20)
+        .build();
+    let defs: EnemyDefs = ron::from_str(SWARM_DEFS).unwrap();
+    defs.validate().unwrap();
+    app.world_mut().insert_resource(defs);
+    while app.count::<Enemy>() == 0 {
+        app.step(1);
+    }
+
+    let positions = app.positions::<Enemy>();
+    assert_eq!(positions.len(), 20);
+    for pos in &positions {
+        let pack_mates = positions
+            .iter()
+            .filter(|other| other.distance(*pos) <= PACK_SPREAD * 2.0)
+            .count();
+        assert!(pack_mates >= 5, "{pos} is in a pack of {pack_mates}");
+    }
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use infinite_shooter_game::enemy::{Enemy, EnemyType};
+use infinite_shooter_game::enemy_def::EnemyDefs;
+use infinite_shooter_game::game_config::GameConfig;
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::ron_asset::RonAsset;
+use infinite_shooter_game::stats::RunStats;
+use infinite_shooter_game::SPRITE_SCALE_FACTOR;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+const DEFS: &str = "{
+    Green: (health: 100.0, speed: 50.0, damage: 1.0, mass: 10.0, collider_radius: 8.0, score: 1, spawn_weight: 3),
+    Red: (health: 100.0, speed: 50.0, damage: 1.0, mass: 10.0, collider_radius: 8.0, score: 1, spawn_weight: 1),
+    Skin: (health: 100.0, speed: 50.0, damage: 1.0, mass: 10.0, collider_radius: 8.0, score: 1, spawn_weight: 0),
+    White: (health: 100.0, speed: 50.0, damage: 1.0, mass: 10.0, collider_radius: 8.0, score: 1, spawn_weight: 0),
+}";
+
+#[test]
+fn spawned_enemies_use_their_type_stats() {
+    let mut config = GameConfig::default();
+    config.enemy.health_scale = 2.0;
+    let mut app = HeadlessApp::builder().config(config).seed(4).build();
+    app.step_secs(1.5);
+
+    let defs = app.world().resource::<EnemyDefs>().clone();
+    defs.validate().unwrap();
+    let world = app.world_mut();
+    let mut enemies = world.query::<(&Enemy, &EnemyType, &Collider)>();
+    assert!(enemies.iter(world).count() > 0);
+    for (enemy, enemy_type, collider) in enemies.iter(world) {
+        let def = defs.get(*enemy_type);
+        assert_eq!(enemy.health, def.health * 2.0);
+        let radius = collider.as_ball().unwrap().radius();
+        assert_eq!(radius, def.collider_radius * SPRITE_SCALE_FACTOR);
+    }
+}
+
+#[test]
+fn spawn_weights_set_how_often_each_type_appears() {
+    let defs: EnemyDefs = ron::from_str(DEFS).unwrap();
+    defs.validate().unwrap();
+
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    let mut counts = [0; 4];
+    for _ in 0..4000 {
+        let enemy_type = defs.random_type(&mut rng);
+        let index = EnemyType::ALL.iter().position(|&t| t == enemy_type);
+        counts[index.unwrap()] += 1;
+    }
+
+    assert!((2800..3200).contains(&counts[0]), "{counts:?}");
+    assert_eq!(counts[2] + counts[3], 0);
+}
+
+#[test]
+fn contact_damage_depends_on_the_enemy_type() {
+    let mut app = common::frozen()
+        .configure(|config| config.enemy.damage_scale = 3.0)
+        .build();
+    app.world_mut().spawn((
+        TransformBundle::from_transform(Transform::from_xyz(30.0, 0.0, 1.0)),
+        Enemy {
+            health: 100.0,
+            last_hit_by: None,
+        },
+        EnemyType::White,
+        Collider::ball(8.0),
+        Sensor,
+    ));
+    while app.world().resource::<RunStats>().damage_taken == 0.0 {
+        app.step(1);
+    }
+
+    let damage = app
+        .world()
+        .resource::<EnemyDefs>()
+        .get(EnemyType::White)
+        .damage;
+    let frame_secs = app.world().resource::<Time>().delta_seconds();
+    let damage_taken = app.world().resource::<RunStats>().damage_taken;
+    assert!((damage_taken - damage * 3.0 * frame_secs).abs() < 1e-4);
+}
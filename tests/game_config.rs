@@ -0,0 +1,82 @@
+use infinite_shooter_game::game_config::{ConfigError, GameConfig};
+
+fn invalid_key(source: &str) -> String {
+    match GameConfig::from_bytes(source.as_bytes()) {
+        Err(ConfigError::Invalid { key, .. }) => key,
+        other => panic!("expected an invalid key, got {other:?}"),
+    }
+}
+
+#[test]
+fn missing_keys_fall_back_to_the_defaults() {
+    let config = GameConfig::from_bytes(b"(player: (speed: 300.0))").unwrap();
+    assert_eq!(config.player.speed, 300.0);
+    assert_eq!(config.player.health, GameConfig::default().player.health);
+    assert_eq!(config.enemy, GameConfig::default().enemy);
+}
+
+#[test]
+fn bad_values_name_their_key() {
+    assert_eq!(invalid_key("(player: (speed: -1.0))"), "player.speed");
+    assert_eq!(invalid_key("(world: (height: 0.0))"), "world.height");
+    assert_eq!(
+        invalid_key("(aim: (assist_strength: 1.5))"),
+        "aim.assist_strength"
+    );
+
+    let err = GameConfig::from_bytes(b"(elite: (affixes: 0))").unwrap_err();
+    assert_eq!(err.to_string(), "`elite.affixes` must be between 1 and 5");
+}
+
+#[test]
+fn limits_name_their_key() {
+    assert_eq!(
+        invalid_key("(elite: (base_chance: 0.5, max_chance: 0.2))"),
+        "elite.base_chance"
+    );
+    assert_eq!(
+        invalid_key("(gun: (projectile_pool_size: 1000000000000))"),
+        "gun.projectile_pool_size"
+    );
+    let err = GameConfig::from_bytes(b"(gun: (weapons: []))").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`gun.weapons` must list between 1 and 9 weapons"
+    );
+}
+
+#[test]
+fn unknown_fields_name_their_key() {
+    assert_eq!(
+        invalid_key("(enemy: (max_enemies: 10))"),
+        "enemy.max_enemies"
+    );
+    assert_eq!(
+        invalid_key("(gun: (bullet_speed: 10.0))"),
+        "gun.bullet_speed"
+    );
+    assert_eq!(invalid_key("(difficulty: 3)"), "difficulty");
+    for section in [
+        "world",
+        "player",
+        "enemy",
+        "gun",
+        "collision",
+        "score",
+        "aim",
+        "elite",
+    ] {
+        assert_eq!(
+            invalid_key(&format!("({section}: (typo: 1))")),
+            format!("{section}.typo")
+        );
+    }
+}
+
+#[test]
+fn syntax_errors_are_reported_as_parse_errors() {
+    assert!(matches!(
+        GameConfig::from_bytes(b"(player: (speed: )"),
+        Err(ConfigError::Parse(_))
+    ));
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{set_fire, set_player_health};
+use infinite_shooter_game::enemy::EnemyType;
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::state::GameState;
+use infinite_shooter_game::stats::RunStats;
+use infinite_shooter_game::world::{GameEntity, Tree};
+
+#[test]
+fn player_death_ends_the_run_with_a_summary() {
+    let mut app = HeadlessApp::builder().seed(5).build();
+    set_fire(&mut app, true);
+    app.step_secs(2.0);
+
+    set_player_health(&mut app, 0.0);
+    app.step(2);
+    assert_eq!(app.state(), GameState::GameOver);
+    assert_eq!(app.count::<GameEntity>(), 0);
+
+    let stats = app.world().resource::<RunStats>().clone();
+    assert!((stats.survival_time.as_secs_f32() - 2.0).abs() < 0.1);
+    assert!(stats.shots_fired > 0);
+
+    // The summary stays put until the next run starts.
+    app.step_secs(1.0);
+    assert_eq!(
+        app.world().resource::<RunStats>().survival_time,
+        stats.survival_time
+    );
+
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::GameInit);
+    app.step(2);
+    assert_eq!(app.state(), GameState::InGame);
+    assert!(app.world().resource::<RunStats>().shots_fired <= 100);
+}
+
+/// The trees two seconds into a run, and the types of the enemies spawned by
+/// then. Physics doesn't settle enemies at the same positions after a
+/// restart, so those aren't compared.
+fn run_layout(app: &mut HeadlessApp) -> (Vec<Vec2>, Vec<EnemyType>) {
+    app.step_secs(2.0);
+    let world = app.world_mut();
+    let enemy_types = world.query::<&EnemyType>().iter(world).copied().collect();
+    (app.positions::<Tree>(), enemy_types)
+}
+
+#[test]
+fn restarted_runs_with_the_same_seed_play_out_the_same() {
+    let mut app = HeadlessApp::builder().seed(7).build();
+    let first = run_layout(&mut app);
+    assert!(!first.0.is_empty());
+
+    set_player_health(&mut app, 0.0);
+    app.step(2);
+    assert_eq!(app.state(), GameState::GameOver);
+    assert_eq!(app.count::<Tree>(), 0);
+
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::GameInit);
+    while app.state() != GameState::InGame {
+        app.step(1);
+    }
+    assert_eq!(run_layout(&mut app), first);
+}
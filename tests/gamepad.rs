@@ -0,0 +1,118 @@
+mod common;
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::input::gamepad::{
+    GamepadAxisChangedEvent, GamepadButtonChangedEvent, GamepadConnection, GamepadConnectionEvent,
+    GamepadEvent, GamepadInfo,
+};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use common::{gun_angle, send_key};
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::input::ActiveInputDevice;
+use infinite_shooter_game::replay::{Replay, ReplayRecorder};
+use infinite_shooter_game::stats::RunStats;
+
+const GAMEPAD: Gamepad = Gamepad { id: 0 };
+
+fn app() -> HeadlessApp {
+    let mut app = common::empty().record().build();
+    app.world_mut()
+        .send_event(GamepadEvent::Connection(GamepadConnectionEvent::new(
+            GAMEPAD,
+            GamepadConnection::Connected(GamepadInfo {
+                name: "Test gamepad".to_string(),
+            }),
+        )));
+    app.step(1);
+    app
+}
+
+fn set_axis(app: &mut HeadlessApp, axis_type: GamepadAxisType, value: f32) {
+    app.world_mut()
+        .send_event(GamepadEvent::Axis(GamepadAxisChangedEvent::new(
+            GAMEPAD, axis_type, value,
+        )));
+}
+
+fn set_button(app: &mut HeadlessApp, button_type: GamepadButtonType, value: f32) {
+    app.world_mut()
+        .send_event(GamepadEvent::Button(GamepadButtonChangedEvent::new(
+            GAMEPAD,
+            button_type,
+            value,
+        )));
+}
+
+fn active_device(app: &HeadlessApp) -> ActiveInputDevice {
+    *app.world().resource::<ActiveInputDevice>()
+}
+
+#[test]
+fn left_stick_moves_at_analog_speed() {
+    let mut app = app();
+    set_axis(&mut app, GamepadAxisType::LeftStickX, -0.5);
+    app.step_secs(1.0);
+
+    assert_eq!(active_device(&app), ActiveInputDevice::Gamepad(GAMEPAD));
+    // Half of the 120 units per second player speed.
+    let x = app.player_transform().unwrap().translation.x;
+    assert!((x + 60.0).abs() < 2.0, "{x}");
+}
+
+#[test]
+fn right_stick_aims_the_gun_and_trigger_fires() {
+    let mut app = app();
+    set_axis(&mut app, GamepadAxisType::RightStickY, 1.0);
+    app.step(2);
+    assert!((gun_angle(&mut app) - FRAC_PI_2).abs() < 1e-3);
+
+    // The gun keeps its heading when the stick is let go.
+    set_axis(&mut app, GamepadAxisType::RightStickY, 0.0);
+    set_button(&mut app, GamepadButtonType::RightTrigger2, 1.0);
+    app.step_secs(1.0);
+    assert!((gun_angle(&mut app) - FRAC_PI_2).abs() < 1e-3);
+    assert!(app.world().resource::<RunStats>().shots_fired > 0);
+}
+
+#[test]
+fn last_used_device_becomes_active() {
+    let mut app = app();
+    assert_eq!(active_device(&app), ActiveInputDevice::KeyboardMouse);
+
+    set_button(&mut app, GamepadButtonType::South, 1.0);
+    app.step(1);
+    assert_eq!(active_device(&app), ActiveInputDevice::Gamepad(GAMEPAD));
+
+    send_key(&mut app, KeyCode::KeyD, ButtonState::Pressed);
+    app.step(1);
+    assert_eq!(active_device(&app), ActiveInputDevice::KeyboardMouse);
+
+    set_axis(&mut app, GamepadAxisType::LeftStickY, 0.9);
+    app.step(1);
+    assert_eq!(active_device(&app), ActiveInputDevice::Gamepad(GAMEPAD));
+}
+
+#[test]
+fn replay_reproduces_analog_input() {
+    let mut app = app();
+    set_axis(&mut app, GamepadAxisType::LeftStickX, 0.3);
+    set_axis(&mut app, GamepadAxisType::LeftStickY, -0.7);
+    set_axis(&mut app, GamepadAxisType::RightStickX, -1.0);
+    app.step(60);
+
+    let replay = app
+        .world()
+        .resource::<ReplayRecorder>()
+        .replay()
+        .unwrap()
+        .clone();
+    assert_eq!(Replay::from_bytes(&replay.to_bytes()).unwrap(), replay);
+
+    let mut replayed = HeadlessApp::builder().replay(replay).build();
+    // Connecting the gamepad took one more frame.
+    replayed.step(61);
+    assert_eq!(replayed.player_transform(), app.player_transform());
+    assert_eq!(gun_angle(&mut replayed), gun_angle(&mut app));
+}
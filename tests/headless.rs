@@ -0,0 +1,33 @@
+use infinite_shooter_game::enemy::Enemy;
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::player::Player;
+use infinite_shooter_game::state::GameState;
+
+#[test]
+fn headless_app_reaches_in_game() {
+    let mut app = HeadlessApp::new();
+
+    assert_eq!(app.state(), GameState::InGame);
+    assert_eq!(app.count::<Player>(), 1);
+}
+
+#[test]
+fn headless_app_spawns_enemies() {
+    let mut app = HeadlessApp::new();
+    app.step_secs(2.0);
+
+    assert_eq!(app.state(), GameState::InGame);
+    assert!(app.count::<Enemy>() > 0);
+}
+
+#[test]
+fn same_seed_produces_same_run() {
+    let enemy_positions = |seed| {
+        let mut app = HeadlessApp::builder().seed(seed).build();
+        app.step_secs(1.5);
+        app.positions::<Enemy>()
+    };
+
+    assert_eq!(enemy_positions(7), enemy_positions(7));
+    assert_ne!(enemy_positions(7), enemy_positions(8));
+}
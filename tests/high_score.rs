@@ -0,0 +1,94 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use common::set_player_health;
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::high_score::{GameMode, HighScoreEntry, HighScores};
+use infinite_shooter_game::state::GameState;
+use infinite_shooter_game::NUM_HIGH_SCORES;
+
+fn temp_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "infinite_shooter_game_test_{}_{name}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    dir.join("high_scores.ron")
+}
+
+fn entry(score: u64) -> HighScoreEntry {
+    HighScoreEntry {
+        score,
+        survival_secs: 61.5,
+        kills: 12,
+        seed: 42,
+        timestamp: 1_700_000_000,
+        mode: GameMode::Seeded,
+    }
+}
+
+#[test]
+fn table_keeps_the_best_runs_in_order() {
+    let path = temp_path("order");
+    let mut high_scores = HighScores::load_or_default(&path);
+    assert!(high_scores.entries().is_empty());
+
+    for score in 0..NUM_HIGH_SCORES as u64 + 5 {
+        high_scores.insert(entry(score * 10));
+    }
+    assert_eq!(high_scores.insert(entry(0)), None);
+    assert_eq!(high_scores.insert(entry(1000)), Some(0));
+
+    let scores: Vec<u64> = high_scores.entries().iter().map(|e| e.score).collect();
+    assert_eq!(scores.len(), NUM_HIGH_SCORES);
+    assert!(scores.windows(2).all(|pair| pair[0] >= pair[1]));
+    assert_eq!(scores[0], 1000);
+
+    high_scores.save(&path).unwrap();
+    let loaded = HighScores::load_or_default(&path);
+    assert_eq!(loaded.entries(), high_scores.entries());
+    assert_eq!(loaded.entries()[0].date(), "2023-11-14");
+}
+
+#[test]
+fn corrupted_or_outdated_files_are_moved_aside() {
+    for (name, contents) in [
+        ("corrupted", "(version: 1, entries: [(score: "),
+        ("outdated", "(version: 99, entries: [])"),
+    ] {
+        let path = temp_path(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, contents).unwrap();
+
+        let high_scores = HighScores::load_or_default(&path);
+        assert!(high_scores.entries().is_empty());
+        assert!(!path.exists());
+        assert_eq!(
+            fs::read_to_string(path.with_extension("ron.bak")).unwrap(),
+            contents
+        );
+    }
+}
+
+#[test]
+fn game_over_records_the_run() {
+    let path = temp_path("game_over");
+    let mut app = HeadlessApp::builder()
+        .seed(9)
+        .high_scores(path.clone())
+        .build();
+    app.step_secs(1.0);
+    set_player_health(&mut app, 0.0);
+    app.step(2);
+    assert_eq!(app.state(), GameState::GameOver);
+
+    let loaded = HighScores::load_or_default(&path);
+    let [entry] = loaded.entries() else {
+        panic!("expected one high score, got {:?}", loaded.entries());
+    };
+    assert_eq!(entry.seed, 9);
+    assert_eq!(entry.mode, GameMode::Seeded);
+    assert!(entry.survival_secs > 0.9);
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use bevy::prelude::*;
+use common::set_fire;
+use infinite_shooter_game::enemy::{Enemy, EnemyType};
+use infinite_shooter_game::enemy_def::EnemyDefs;
+use infinite_shooter_game::game_config::GameConfig;
+use infinite_shooter_game::gun::Bullet;
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::stats::RunStats;
+use infinite_shooter_game::weapon::{WeaponDef, Weapons};
+
+const FRAME_RATES: [f32; 3] = [30.0, 60.0, 144.0];
+
+/// An empty world, so nothing collides with what is being measured.
+fn app(frame_rate: f32) -> HeadlessApp {
+    common::empty()
+        // A single bullet that outlives the measurement.
+        .configure(|config| config.gun.weapons = vec!["sniper".to_string()])
+        .timestep(1.0 / frame_rate)
+        .build()
+}
+
+fn assert_all_close(distances: &[f32], expected: f32) {
+    for distance in distances {
+        assert!(
+            (distance - expected).abs() < expected * 0.01,
+            "travelled {distances:?}, expected {expected}"
+        );
+    }
+}
+
+#[test]
+fn player_speed_does_not_depend_on_frame_rate() {
+    let distances: Vec<f32> = FRAME_RATES
+        .iter()
+        .map(|&frame_rate| {
+            let mut app = app(frame_rate);
+            let start = app.player_transform().unwrap().translation;
+            app.world_mut()
+                .resource_mut::<ButtonInput<KeyCode>>()
+                .press(KeyCode::KeyD);
+            app.step_secs(1.0);
+            app.player_transform().unwrap().translation.distance(start)
+        })
+        .collect();
+
+    assert_all_close(&distances, GameConfig::default().player.speed);
+}
+
+#[test]
+fn enemy_speed_does_not_depend_on_frame_rate() {
+    let mut expected = 0.0;
+    let distances: Vec<f32> = FRAME_RATES
+        .iter()
+        .map(|&frame_rate| {
+            let mut app = app(frame_rate);
+            let start = Vec3::new(2000.0, 0.0, 1.0);
+            let enemy = app
+                .world_mut()
+                .spawn((
+                    Transform::from_translation(start),
+                    Enemy {
+                        health: 100.0,
+                        last_hit_by: None,
+                    },
+                    EnemyType::Red,
+                ))
+                .id();
+            expected = app
+                .world()
+                .resource::<EnemyDefs>()
+                .get(EnemyType::Red)
+                .speed;
+            app.step_secs(1.0);
+            app.world()
+                .get::<Transform>(enemy)
+                .unwrap()
+                .translation
+                .distance(start)
+        })
+        .collect();
+
+    assert_all_close(&distances, expected);
+}
+
+#[test]
+fn contact_damage_does_not_depend_on_frame_rate() {
+    let mut expected = 0.0;
+    let damage: Vec<f32> = FRAME_RATES
+        .iter()
+        .map(|&frame_rate| {
+            let mut app = app(frame_rate);
+            let player = app.player_transform().unwrap().translation.truncate();
+            let enemy = common::spawn_enemy(&mut app, player, 100.0);
+            app.world_mut().entity_mut(enemy).insert(EnemyType::Green);
+            expected = app
+                .world()
+                .resource::<EnemyDefs>()
+                .get(EnemyType::Green)
+                .damage;
+
+            let damage_taken = |app: &HeadlessApp| app.world().resource::<RunStats>().damage_taken;
+            while damage_taken(&app) == 0.0 {
+                app.step(1);
+            }
+            let start = damage_taken(&app);
+            app.step_secs(1.0);
+            damage_taken(&app) - start
+        })
+        .collect();
+
+    assert_all_close(&damage, expected);
+}
+
+#[test]
+fn bullet_speed_does_not_depend_on_frame_rate() {
+    let distances: Vec<f32> = FRAME_RATES
+        .iter()
+        .map(|&frame_rate| {
+            let mut app = app(frame_rate);
+            set_fire(&mut app, true);
+            while app.count::<Bullet>() == 0 {
+                app.step(1);
+            }
+            set_fire(&mut app, false);
+
+            let start = app.positions::<Bullet>()[0];
+            app.step_secs(0.5);
+            let bullets = app.positions::<Bullet>();
+            assert_eq!(bullets.len(), 1);
+            bullets[0].distance(start)
+        })
+        .collect();
+
+    let app = app(60.0);
+    let sniper = app.world().resource::<Weapons>().get("sniper").unwrap();
+    let speed = app
+        .world()
+        .resource::<Assets<WeaponDef>>()
+        .get(&sniper)
+        .unwrap()
+        .projectile_speed;
+    assert_all_close(&distances, speed * 0.5);
+}
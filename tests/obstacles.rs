@@ -0,0 +1,90 @@
+mod common;
+
+use bevy::ecs::event::ManualEventReader;
+use bevy::math::vec2;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::projectile::{ProjectileImpact, ProjectileModifiers};
+use infinite_shooter_game::world::Tree;
+
+fn spawn_tree(app: &mut HeadlessApp, x: f32) -> Entity {
+    let tree = app
+        .world_mut()
+        .spawn((
+            TransformBundle::from_transform(Transform::from_xyz(x, 0.0, 0.0)),
+            Tree,
+            RigidBody::Fixed,
+            Collider::cuboid(20.0, 200.0),
+        ))
+        .id();
+    app.step_secs(0.2);
+    tree
+}
+
+fn spawn_bullet(app: &mut HeadlessApp, pos: Vec2, modifiers: ProjectileModifiers) -> Entity {
+    common::spawn_bullet(app, pos, Vec2::X * 1000.0, 10.0, modifiers)
+}
+
+/// Steps `frames` frames and collects every impact sent meanwhile.
+fn collect_impacts(app: &mut HeadlessApp, frames: u32) -> Vec<ProjectileImpact> {
+    let mut reader = ManualEventReader::<ProjectileImpact>::default();
+    let mut impacts = Vec::new();
+    for _ in 0..frames {
+        app.step(1);
+        let events = app.world().resource::<Events<ProjectileImpact>>();
+        impacts.extend(reader.read(events).copied());
+    }
+    impacts
+}
+
+#[test]
+fn bullets_stop_at_obstacles() {
+    let mut app = common::empty().build();
+    let tree = spawn_tree(&mut app, 400.0);
+    let bullet = spawn_bullet(&mut app, vec2(200.0, 0.0), ProjectileModifiers::default());
+    let impacts = collect_impacts(&mut app, 30);
+
+    assert!(app.world().get_entity(bullet).is_none());
+    assert_eq!(impacts.len(), 1);
+    assert_eq!(impacts[0].projectile, bullet);
+    assert_eq!(impacts[0].obstacle, tree);
+    assert!(!impacts[0].ricocheted);
+    assert!((impacts[0].point.x - 380.0).abs() < 0.01);
+    assert_eq!(impacts[0].normal, Vec2::NEG_X);
+}
+
+#[test]
+fn ricocheting_bullets_report_each_bounce() {
+    let mut app = common::empty().build();
+    spawn_tree(&mut app, 400.0);
+    spawn_tree(&mut app, 0.0);
+    let modifiers = ProjectileModifiers {
+        ricochet: 2,
+        ..default()
+    };
+    let bullet = spawn_bullet(&mut app, vec2(200.0, 0.0), modifiers);
+    let impacts = collect_impacts(&mut app, 60);
+
+    let bounces: Vec<bool> = impacts.iter().map(|impact| impact.ricocheted).collect();
+    assert_eq!(bounces, [true, true, false]);
+    assert!(app.world().get_entity(bullet).is_none());
+}
+
+#[test]
+fn a_full_spread_of_bullets_stops_at_a_wall() {
+    let mut app = common::empty().build();
+    spawn_tree(&mut app, 400.0);
+    let bullets: Vec<Entity> = (0..100)
+        .map(|i| {
+            let y = i as f32 * 3.0 - 150.0;
+            spawn_bullet(&mut app, vec2(200.0, y), ProjectileModifiers::default())
+        })
+        .collect();
+    let impacts = collect_impacts(&mut app, 30);
+
+    assert_eq!(impacts.len(), 100);
+    assert!(bullets
+        .iter()
+        .all(|&bullet| app.world().get_entity(bullet).is_none()));
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{set_fire, tap_key};
+use infinite_shooter_game::enemy::Enemy;
+use infinite_shooter_game::gun::Bullet;
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::state::GameState;
+
+#[test]
+fn pause_freezes_the_run() {
+    let mut app = HeadlessApp::builder().seed(1).build();
+    set_fire(&mut app, true);
+    app.step_secs(1.5);
+
+    tap_key(&mut app, KeyCode::Escape);
+    assert_eq!(app.state(), GameState::Paused);
+
+    let player = app.player_transform();
+    let enemies = app.positions::<Enemy>();
+    let num_bullets = app.count::<Bullet>();
+    assert!(num_bullets > 0);
+
+    app.step_secs(5.0);
+    assert_eq!(app.player_transform(), player);
+    assert_eq!(app.positions::<Enemy>(), enemies);
+    assert_eq!(app.count::<Bullet>(), num_bullets);
+
+    tap_key(&mut app, KeyCode::KeyP);
+    assert_eq!(app.state(), GameState::InGame);
+    assert!(app.count::<Bullet>() > 0);
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use common::set_fire;
+use infinite_shooter_game::game_config::{GameConfig, GameConfigReloaded};
+use infinite_shooter_game::gun::Bullet;
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::projectile::ProjectilePool;
+
+const SHOTGUN_PROJECTILES: usize = 12;
+
+fn app(pool_size: usize) -> HeadlessApp {
+    common::empty()
+        .configure(|config| {
+            config.gun.weapons = vec!["shotgun".to_string()];
+            config.gun.projectile_pool_size = pool_size;
+        })
+        .build()
+}
+
+/// Fires one shot and waits until its projectiles are gone.
+fn fire_and_expire(app: &mut HeadlessApp) {
+    set_fire(app, true);
+    while app.count::<Bullet>() == 0 {
+        app.step(1);
+    }
+    assert_eq!(app.count::<Bullet>(), SHOTGUN_PROJECTILES);
+    set_fire(app, false);
+    app.step_secs(1.0);
+    assert_eq!(app.count::<Bullet>(), 0);
+}
+
+#[test]
+fn expired_projectiles_are_reused() {
+    let mut app = app(64);
+    fire_and_expire(&mut app);
+    let free = app.world().resource::<ProjectilePool>().free();
+    let entities = app.world().entities().len();
+    assert_eq!(free, SHOTGUN_PROJECTILES);
+
+    fire_and_expire(&mut app);
+    assert_eq!(app.world().resource::<ProjectilePool>().free(), free);
+    assert_eq!(app.world().entities().len(), entities);
+}
+
+#[test]
+fn projectiles_past_the_pool_size_are_despawned() {
+    let mut app = app(4);
+    fire_and_expire(&mut app);
+    let entities = app.world().entities().len();
+    assert_eq!(app.world().resource::<ProjectilePool>().free(), 4);
+
+    fire_and_expire(&mut app);
+    assert_eq!(app.world().resource::<ProjectilePool>().free(), 4);
+    assert_eq!(app.world().entities().len(), entities);
+}
+
+#[test]
+fn reloading_the_config_resizes_the_pool() {
+    let mut app = app(64);
+    fire_and_expire(&mut app);
+    let entities = app.world().entities().len();
+    assert_eq!(
+        app.world().resource::<ProjectilePool>().free(),
+        SHOTGUN_PROJECTILES
+    );
+
+    let world = app.world_mut();
+    world.resource_mut::<GameConfig>().gun.projectile_pool_size = 4;
+    world.send_event(GameConfigReloaded);
+    app.step(1);
+    let pool = app.world().resource::<ProjectilePool>();
+    assert_eq!(pool.size(), 4);
+    assert_eq!(pool.free(), 4);
+    assert_eq!(
+        app.world().entities().len(),
+        entities - (SHOTGUN_PROJECTILES as u32 - 4)
+    );
+}
@@ -0,0 +1,217 @@
+mod common;
+
+use bevy::math::vec2;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use common::{enemy_health, spawn_bullet, spawn_enemy};
+use infinite_shooter_game::enemy::{DamageSource, Enemy};
+use infinite_shooter_game::game_config::ConfigError;
+use infinite_shooter_game::gun::BulletVelocity;
+use infinite_shooter_game::projectile::{Explosive, Homing, ProjectileModifiers};
+use infinite_shooter_game::world::Tree;
+
+const DAMAGE: f32 = 15.0;
+
+#[test]
+fn homing_bullets_turn_toward_the_nearest_enemy() {
+    let mut app = common::frozen().build();
+    let enemy = spawn_enemy(&mut app, vec2(400.0, 150.0), 100.0);
+    app.step_secs(0.2);
+    let modifiers = ProjectileModifiers {
+        homing: Some(Homing {
+            turn_rate_degrees: 360.0,
+            range: 1000.0,
+        }),
+        ..default()
+    };
+    spawn_bullet(
+        &mut app,
+        vec2(100.0, 0.0),
+        vec2(300.0, 0.0),
+        DAMAGE,
+        modifiers,
+    );
+    app.step_secs(2.0);
+
+    assert_eq!(enemy_health(&app, enemy), 100.0 - DAMAGE);
+}
+
+#[test]
+fn explosive_bullets_damage_enemies_in_the_blast_radius() {
+    let mut app = common::frozen().build();
+    let hit = spawn_enemy(&mut app, vec2(300.0, 0.0), 100.0);
+    let nearby = spawn_enemy(&mut app, vec2(300.0, 60.0), 100.0);
+    let far = spawn_enemy(&mut app, vec2(300.0, 300.0), 100.0);
+    app.step_secs(0.2);
+    let modifiers = ProjectileModifiers {
+        explosive: Some(Explosive {
+            radius: 50.0,
+            damage: 30.0,
+        }),
+        ..default()
+    };
+    spawn_bullet(&mut app, vec2(280.0, 0.0), Vec2::ZERO, DAMAGE, modifiers);
+    app.step_secs(0.2);
+
+    assert_eq!(enemy_health(&app, hit), 100.0 - DAMAGE - 30.0);
+    assert_eq!(enemy_health(&app, nearby), 100.0 - 30.0);
+    assert_eq!(enemy_health(&app, far), 100.0);
+    assert_eq!(
+        app.world().get::<Enemy>(nearby).unwrap().last_hit_by,
+        Some(DamageSource::Explosion)
+    );
+}
+
+#[test]
+fn piercing_explosive_bullets_explode_once_when_used_up() {
+    let mut app = common::frozen().build();
+    let first = spawn_enemy(&mut app, vec2(300.0, 0.0), 100.0);
+    let second = spawn_enemy(&mut app, vec2(340.0, 0.0), 100.0);
+    let bystander = spawn_enemy(&mut app, vec2(320.0, 60.0), 100.0);
+    app.step_secs(0.2);
+    let modifiers = ProjectileModifiers {
+        pierce: 1,
+        explosive: Some(Explosive {
+            radius: 50.0,
+            damage: 30.0,
+        }),
+        ..default()
+    };
+    let bullet = spawn_bullet(&mut app, vec2(318.0, 0.0), Vec2::ZERO, DAMAGE, modifiers);
+    app.step_secs(0.2);
+
+    assert!(app.world().get_entity(bullet).is_none());
+    assert_eq!(enemy_health(&app, first), 100.0 - DAMAGE - 30.0);
+    assert_eq!(enemy_health(&app, second), 100.0 - DAMAGE - 30.0);
+    assert_eq!(enemy_health(&app, bystander), 100.0 - 30.0);
+}
+
+#[test]
+fn ricocheting_bullets_bounce_off_trees() {
+    let mut app = common::frozen().build();
+    app.world_mut().spawn((
+        TransformBundle::from_transform(Transform::from_xyz(400.0, 0.0, 0.0)),
+        Tree,
+        RigidBody::Fixed,
+        Collider::cuboid(20.0, 100.0),
+    ));
+    app.step_secs(0.2);
+    let bouncing = spawn_bullet(
+        &mut app,
+        vec2(200.0, 0.0),
+        vec2(600.0, 0.0),
+        DAMAGE,
+        ProjectileModifiers {
+            ricochet: 1,
+            ..default()
+        },
+    );
+    app.step_secs(0.5);
+
+    let velocity = app.world().get::<BulletVelocity>(bouncing).unwrap();
+    assert!(velocity.0.x < 0.0);
+    let pos = app.world().get::<Transform>(bouncing).unwrap().translation;
+    assert!(pos.x < 380.0);
+}
+
+#[test]
+fn ricocheting_bullets_do_not_pass_through_the_next_obstacle() {
+    let mut app = common::frozen().build();
+    for x in [330.0, 400.0] {
+        app.world_mut().spawn((
+            TransformBundle::from_transform(Transform::from_xyz(x, 0.0, 0.0)),
+            Tree,
+            RigidBody::Fixed,
+            Collider::cuboid(10.0, 100.0),
+        ));
+    }
+    app.step_secs(0.2);
+    // Moves 100 a frame, so a full frame after the bounce would clear the
+    // first tree.
+    let bullet = spawn_bullet(
+        &mut app,
+        vec2(380.0, 0.0),
+        vec2(6000.0, 0.0),
+        DAMAGE,
+        ProjectileModifiers {
+            ricochet: 1,
+            ..default()
+        },
+    );
+    app.step(3);
+
+    assert!(app.world().get_entity(bullet).is_none());
+}
+
+#[test]
+fn combined_modifiers_stack_pierce_and_ricochet() {
+    let weapon = ProjectileModifiers {
+        pierce: 2,
+        ricochet: 1,
+        ..default()
+    };
+    let upgrade = ProjectileModifiers {
+        pierce: 1,
+        ricochet: 2,
+        explosive: Some(Explosive {
+            radius: 40.0,
+            damage: 10.0,
+        }),
+        ..default()
+    };
+    let combined = weapon.combine(&upgrade);
+
+    assert_eq!(combined.pierce, 3);
+    assert_eq!(combined.ricochet, 3);
+    assert_eq!(combined.explosive, upgrade.explosive);
+    assert_eq!(combined.homing, None);
+}
+
+#[test]
+fn negative_modifier_values_are_rejected() {
+    let invalid_key = |modifiers: ProjectileModifiers| match modifiers.validate() {
+        Err(ConfigError::Invalid { key, .. }) => key,
+        other => panic!("expected an invalid key, got {other:?}"),
+    };
+
+    assert_eq!(
+        invalid_key(ProjectileModifiers {
+            homing: Some(Homing {
+                turn_rate_degrees: -90.0,
+                range: 300.0,
+            }),
+            ..default()
+        }),
+        "modifiers.homing.turn_rate_degrees"
+    );
+    assert_eq!(
+        invalid_key(ProjectileModifiers {
+            homing: Some(Homing {
+                turn_rate_degrees: 90.0,
+                range: -1.0,
+            }),
+            ..default()
+        }),
+        "modifiers.homing.range"
+    );
+    assert_eq!(
+        invalid_key(ProjectileModifiers {
+            explosive: Some(Explosive {
+                radius: -50.0,
+                damage: 30.0,
+            }),
+            ..default()
+        }),
+        "modifiers.explosive.radius"
+    );
+    assert_eq!(
+        invalid_key(ProjectileModifiers {
+            explosive: Some(Explosive {
+                radius: 50.0,
+                damage: -30.0,
+            }),
+            ..default()
+        }),
+        "modifiers.explosive.damage"
+    );
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use bevy::prelude::*;
+use infinite_shooter_game::enemy::{Enemy, EnemyType};
+use infinite_shooter_game::enemy_def::{EnemyBehaviour, EnemyDefs, RangedDef};
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::ranged::{EnemyProjectile, RangedState};
+use infinite_shooter_game::ron_asset::RonAsset;
+use infinite_shooter_game::stats::RunStats;
+use infinite_shooter_game::RANGED_DISTANCE_TOLERANCE;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Every type chases. White keeps its speed from `assets/enemies.ron`.
+const CHASE_DEFS: &str = "{
+    Green: (health: 100.0, speed: 100.0, damage: 1.0, mass: 10.0, collider_radius: 8.0, score: 1, spawn_weight: 1),
+    Red: (health: 100.0, speed: 100.0, damage: 1.0, mass: 10.0, collider_radius: 8.0, score: 1, spawn_weight: 1),
+    Skin: (health: 100.0, speed: 100.0, damage: 1.0, mass: 10.0, collider_radius: 8.0, score: 1, spawn_weight: 1),
+    White: (health: 100.0, speed: 100.0, damage: 1.0, mass: 10.0, collider_radius: 8.0, score: 1, spawn_weight: 1),
+}";
+
+fn ranged_def(app: &HeadlessApp) -> RangedDef {
+    let defs = app.world().resource::<EnemyDefs>();
+    let EnemyBehaviour::Ranged(ranged) = &defs.get(EnemyType::White).behaviour else {
+        panic!("White enemies should be ranged");
+    };
+    ranged.clone()
+}
+
+fn spawn_ranged_enemy(app: &mut HeadlessApp, position: Vec2, seed: u64) -> Entity {
+    let ranged = ranged_def(app);
+    let speed = app
+        .world()
+        .resource::<EnemyDefs>()
+        .get(EnemyType::White)
+        .speed;
+    let state = RangedState::new(&ranged, speed, &mut ChaCha8Rng::seed_from_u64(seed));
+    app.world_mut()
+        .spawn((
+            TransformBundle::from_transform(Transform::from_translation(position.extend(1.0))),
+            Sprite::default(),
+            Enemy {
+                health: 100.0,
+                last_hit_by: None,
+            },
+            EnemyType::White,
+            state,
+        ))
+        .id()
+}
+
+#[test]
+fn ranged_enemies_close_in_to_their_preferred_distance() {
+    let mut app = common::empty().build();
+    let player_pos = app.player_transform().unwrap().translation.truncate();
+    let enemy = spawn_ranged_enemy(&mut app, player_pos + Vec2::X * 1200.0, 1);
+    app.step_secs(15.0);
+
+    let enemy_pos = app.world().get::<Transform>(enemy).unwrap().translation;
+    let distance = enemy_pos.truncate().distance(player_pos);
+    let preferred = ranged_def(&app).preferred_distance;
+    assert!(
+        (distance - preferred).abs() <= RANGED_DISTANCE_TOLERANCE * 1.5,
+        "{distance} from the player, preferred {preferred}"
+    );
+}
+
+#[test]
+fn ranged_enemies_keep_their_behaviour_when_the_definitions_change() {
+    let mut app = common::empty().build();
+    let preferred = ranged_def(&app).preferred_distance;
+    let player_pos = app.player_transform().unwrap().translation.truncate();
+    let enemy = spawn_ranged_enemy(&mut app, player_pos + Vec2::X * 1200.0, 1);
+    let defs: EnemyDefs = ron::from_str(CHASE_DEFS).unwrap();
+    defs.validate().unwrap();
+    app.world_mut().insert_resource(defs);
+    app.step_secs(15.0);
+
+    let enemy_pos = app.world().get::<Transform>(enemy).unwrap().translation;
+    let distance = enemy_pos.truncate().distance(player_pos);
+    assert!(
+        (distance - preferred).abs() <= RANGED_DISTANCE_TOLERANCE * 1.5,
+        "{distance} from the player, preferred {preferred}"
+    );
+}
+
+#[test]
+fn shots_are_telegraphed_and_hurt_the_player() {
+    let mut app = common::frozen()
+        .configure(|config| config.enemy.damage_scale = 2.0)
+        .build();
+    let player_pos = app.player_transform().unwrap().translation.truncate();
+    let enemy = spawn_ranged_enemy(&mut app, player_pos + Vec2::Y * 300.0, 1);
+    let ranged = ranged_def(&app);
+
+    let mut frames = 0;
+    while !app
+        .world()
+        .get::<RangedState>(enemy)
+        .unwrap()
+        .is_telegraphing()
+    {
+        app.step(1);
+        frames += 1;
+        assert!(frames < 1000, "never started a shot");
+    }
+    assert_ne!(
+        app.world().get::<Sprite>(enemy).unwrap().color,
+        Color::WHITE
+    );
+    assert_eq!(app.count::<EnemyProjectile>(), 0);
+
+    app.step_secs(ranged.telegraph_secs * 0.8);
+    assert_eq!(app.count::<EnemyProjectile>(), 0);
+    app.step_secs(ranged.telegraph_secs * 0.4);
+    assert_eq!(app.count::<EnemyProjectile>(), 1);
+    assert_eq!(
+        app.world().get::<Sprite>(enemy).unwrap().color,
+        Color::WHITE
+    );
+
+    app.step_secs(300.0 / ranged.projectile_speed + 0.2);
+    assert_eq!(app.count::<EnemyProjectile>(), 0);
+    let damage_taken = app.world().resource::<RunStats>().damage_taken;
+    assert_eq!(damage_taken, ranged.projectile_damage * 2.0);
+}
+
+#[test]
+fn enemy_projectiles_in_flight_are_capped() {
+    let mut app = common::frozen()
+        .configure(|config| config.enemy.max_projectiles = 3)
+        .build();
+    let player_pos = app.player_transform().unwrap().translation.truncate();
+    for i in 0..12 {
+        let dir = Vec2::from_angle(i as f32 * 0.5);
+        spawn_ranged_enemy(&mut app, player_pos + dir * 450.0, i);
+    }
+
+    let mut most_in_flight = 0;
+    for _ in 0..300 {
+        app.step(1);
+        most_in_flight = most_in_flight.max(app.count::<EnemyProjectile>());
+    }
+    assert_eq!(most_in_flight, 3);
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use bevy::prelude::*;
+use common::tap_key;
+use infinite_shooter_game::enemy::Enemy;
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::replay::{Replay, ReplayError, ReplayRecorder};
+use infinite_shooter_game::state::GameState;
+
+fn set_key(app: &mut HeadlessApp, key: KeyCode, pressed: bool) {
+    let mut keyboard_input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    if pressed {
+        keyboard_input.press(key);
+    } else {
+        keyboard_input.release(key);
+    }
+}
+
+#[test]
+fn replay_reproduces_recorded_run() {
+    let mut app = HeadlessApp::builder().seed(3).record().build();
+    set_key(&mut app, KeyCode::KeyD, true);
+    app.step(90);
+    set_key(&mut app, KeyCode::KeyD, false);
+    set_key(&mut app, KeyCode::KeyW, true);
+    app.step(90);
+
+    let recorder = app.world().resource::<ReplayRecorder>();
+    let replay = recorder.replay().unwrap().clone();
+    assert_eq!(Replay::from_bytes(&replay.to_bytes()).unwrap(), replay);
+
+    let mut replayed = HeadlessApp::builder().replay(replay).build();
+    replayed.step(180);
+
+    assert_eq!(replayed.player_transform(), app.player_transform());
+    assert_eq!(replayed.positions::<Enemy>(), app.positions::<Enemy>());
+}
+
+#[test]
+fn replay_reproduces_a_run_paused_midway() {
+    let mut app = HeadlessApp::builder().seed(3).record().build();
+    set_key(&mut app, KeyCode::KeyD, true);
+    app.step(60);
+    tap_key(&mut app, KeyCode::KeyP);
+    assert_eq!(app.state(), GameState::Paused);
+    app.step(30);
+    tap_key(&mut app, KeyCode::KeyP);
+    assert_eq!(app.state(), GameState::InGame);
+    app.step(60);
+
+    let recorder = app.world().resource::<ReplayRecorder>();
+    let replay = recorder.replay().unwrap().clone();
+    let replay = Replay::from_bytes(&replay.to_bytes()).unwrap();
+    // Building the app already plays the first frame.
+    let num_frames = replay.frames.len() as u32 - 1;
+
+    let mut replayed = HeadlessApp::builder().replay(replay).build();
+    replayed.step(num_frames);
+
+    assert_eq!(replayed.player_transform(), app.player_transform());
+    assert_eq!(replayed.positions::<Enemy>(), app.positions::<Enemy>());
+}
+
+#[test]
+fn replay_reproduces_a_slowed_down_run() {
+    let mut app = HeadlessApp::builder().seed(3).record().build();
+    app.world_mut()
+        .resource_mut::<Time<Virtual>>()
+        .set_relative_speed(0.5);
+    set_key(&mut app, KeyCode::KeyD, true);
+    app.step(120);
+
+    let recorder = app.world().resource::<ReplayRecorder>();
+    let replay = recorder.replay().unwrap().clone();
+    let num_frames = replay.frames.len() as u32 - 1;
+
+    // Recorded frame times are already slowed down, so playback must not
+    // slow them down again.
+    let mut replayed = HeadlessApp::builder().replay(replay).build();
+    replayed
+        .world_mut()
+        .resource_mut::<Time<Virtual>>()
+        .set_relative_speed(0.5);
+    replayed.step(num_frames);
+
+    assert_eq!(replayed.player_transform(), app.player_transform());
+    assert_eq!(replayed.positions::<Enemy>(), app.positions::<Enemy>());
+}
+
+#[test]
+fn other_format_versions_are_rejected() {
+    let replay = Replay {
+        seed: 1,
+        frames: Vec::new(),
+    };
+    let mut bytes = replay.to_bytes();
+    bytes[4..6].copy_from_slice(&2u16.to_le_bytes());
+    assert!(matches!(
+        Replay::from_bytes(&bytes),
+        Err(ReplayError::UnsupportedVersion(2))
+    ));
+}
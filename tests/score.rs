@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+use infinite_shooter_game::enemy::{Enemy, EnemyKilled, EnemyType};
+use infinite_shooter_game::enemy_def::EnemyDefs;
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::stats::RunStats;
+
+fn stats(app: &HeadlessApp) -> RunStats {
+    app.world().resource::<RunStats>().clone()
+}
+
+#[test]
+fn dead_enemies_are_counted_and_scored() {
+    let mut app = HeadlessApp::builder().seed(2).build();
+    app.step_secs(1.5);
+    let enemy_type = {
+        let world = app.world_mut();
+        let (mut enemy, enemy_type) = world
+            .query::<(&mut Enemy, &EnemyType)>()
+            .iter_mut(world)
+            .next()
+            .expect("an enemy has spawned");
+        enemy.health = 0.0;
+        *enemy_type
+    };
+    app.step(1);
+
+    let kill_score = app.world().resource::<EnemyDefs>().get(enemy_type).score;
+    let stats = stats(&app);
+    assert_eq!(stats.total_kills(), 1);
+    assert_eq!(stats.kills.get(&enemy_type), Some(&1));
+    assert_eq!(stats.score, kill_score as u64);
+    assert_eq!(stats.combo, 1);
+}
+
+#[test]
+fn rapid_kills_build_a_combo() {
+    let mut app = HeadlessApp::builder().seed(2).build();
+    for _ in 0..25 {
+        app.world_mut().send_event(EnemyKilled {
+            enemy_type: EnemyType::Red,
+            position: Vec2::ZERO,
+            by: None,
+        });
+    }
+    app.step(1);
+
+    // Ten kills at x1, ten at x2, five at x3.
+    let kill_score = app
+        .world()
+        .resource::<EnemyDefs>()
+        .get(EnemyType::Red)
+        .score as u64;
+    let combo_stats = stats(&app);
+    assert_eq!(combo_stats.combo, 25);
+    assert_eq!(combo_stats.score, kill_score * (10 + 20 + 15));
+
+    app.step_secs(2.0);
+    let stats = stats(&app);
+    assert_eq!(stats.combo, 0);
+    assert_eq!(stats.score, combo_stats.score);
+}
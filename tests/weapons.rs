@@ -0,0 +1,70 @@
+mod common;
+
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use common::{active_weapon, send_key, set_fire, tap_key};
+use infinite_shooter_game::gun::Bullet;
+use infinite_shooter_game::headless::HeadlessApp;
+use infinite_shooter_game::ron_asset::RonAsset;
+use infinite_shooter_game::BUILT_IN_WEAPONS;
+
+#[test]
+fn every_built_in_weapon_fires_its_projectiles() {
+    for name in BUILT_IN_WEAPONS {
+        let mut app = common::empty()
+            .configure(|config| config.gun.weapons = vec![name.to_string()])
+            .build();
+
+        let weapon = active_weapon(&mut app);
+        weapon.validate().unwrap();
+
+        set_fire(&mut app, true);
+        while app.count::<Bullet>() == 0 {
+            app.step(1);
+        }
+
+        let world = app.world_mut();
+        let damages: Vec<f32> = world
+            .query::<&Bullet>()
+            .iter(world)
+            .map(|bullet| bullet.damage)
+            .collect();
+        assert_eq!(damages.len(), weapon.projectiles_per_shot, "{name}");
+        assert!(
+            damages.iter().all(|&damage| damage == weapon.damage),
+            "{name}"
+        );
+    }
+}
+
+fn scroll(app: &mut HeadlessApp, y: f32) {
+    app.world_mut().send_event(MouseWheel {
+        unit: MouseScrollUnit::Line,
+        x: 0.0,
+        y,
+        window: Entity::PLACEHOLDER,
+    });
+    app.step(1);
+}
+
+#[test]
+fn number_keys_and_mouse_wheel_switch_weapons() {
+    let mut app = HeadlessApp::builder().seed(1).build();
+    assert_eq!(active_weapon(&mut app).name, "Shotgun");
+
+    tap_key(&mut app, KeyCode::Digit4);
+    assert_eq!(active_weapon(&mut app).name, "Sniper");
+
+    // Down wraps around to the first slot, up goes back.
+    scroll(&mut app, -1.0);
+    assert_eq!(active_weapon(&mut app).name, "Shotgun");
+    scroll(&mut app, 1.0);
+    assert_eq!(active_weapon(&mut app).name, "Sniper");
+
+    // Zooming the camera leaves the weapon alone.
+    send_key(&mut app, KeyCode::ControlLeft, ButtonState::Pressed);
+    app.step(1);
+    scroll(&mut app, 1.0);
+    assert_eq!(active_weapon(&mut app).name, "Sniper");
+}